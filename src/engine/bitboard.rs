@@ -1,4 +1,4 @@
-use super::board::Square;
+use super::board::{SQUARES, Square};
 use ::std::{
     fmt::Debug,
     ops::{
@@ -117,7 +117,7 @@ pub const SQUARE_ARRAY: [u64; 64] = [
     0b1000000000000000000000000000000000000000000000000000000000000000,
 ];
 
-#[derive(Default, PartialEq, Clone)]
+#[derive(Default, PartialEq, Eq, Clone)]
 pub struct BitBoard(u64);
 
 impl BitBoard {
@@ -172,6 +172,18 @@ impl BitBoard {
     pub fn trailing_zeros(&self) -> u32 {
         self.0.trailing_zeros()
     }
+    /// Set squares, from a1 to h8.
+    pub fn squares(&self) -> impl Iterator<Item = Square> + use<> {
+        let mut x = self.0;
+        ::std::iter::from_fn(move || {
+            if x == 0 {
+                return None;
+            }
+            let square = SQUARES[x.trailing_zeros() as usize];
+            x &= x - 1;
+            Some(square)
+        })
+    }
 }
 
 impl From<Square> for BitBoard {
@@ -197,32 +209,67 @@ impl From<u64> for BitBoard {
         BitBoard(value)
     }
 }
+impl BitBoard {
+    /// The board from White's side, rank 8 first, with `1` on set squares and
+    /// file and rank labels. Every line is `DRAWING_WIDTH` characters wide.
+    fn drawing(&self) -> Vec<String> {
+        let mut lines = vec!["  ┌────────┐".to_string()];
+        for rank in (0..8).rev() {
+            let row: String = (0..8)
+                .map(|file| {
+                    if self.0 >> (rank * 8 + file) & 1 == 1 {
+                        '1'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            lines.push(format!("{} │{row}│", rank + 1));
+        }
+        lines.push("  └────────┘".to_string());
+        lines.push("   abcdefgh".to_string());
+        lines
+    }
+}
+
+const DRAWING_WIDTH: usize = 12;
+
+/// Several bitboards drawn next to each other, each under its legend, for
+/// comparing masks such as attacks and blockers. A column is as wide as the
+/// board or its legend, whichever is wider.
+pub fn side_by_side(boards: &[(&str, &BitBoard)]) -> String {
+    let drawings: Vec<Vec<String>> = boards.iter().map(|(_, bb)| bb.drawing()).collect();
+    let widths: Vec<usize> = boards
+        .iter()
+        .map(|(legend, _)| legend.chars().count().max(DRAWING_WIDTH))
+        .collect();
+    let mut lines = vec![
+        boards
+            .iter()
+            .zip(&widths)
+            .map(|((legend, _), width)| format!("{legend:<width$}"))
+            .collect::<Vec<_>>(),
+    ];
+    lines.extend((0..11).map(|row| {
+        drawings
+            .iter()
+            .zip(&widths)
+            .map(|(drawing, width)| format!("{:<width$}", drawing[row]))
+            .collect()
+    }));
+    lines
+        .iter()
+        .map(|line| line.join("  ").trim_end().to_string() + "\n")
+        .collect()
+}
+
 impl Debug for BitBoard {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let l8 = (self.0 & 0xFF00000000000000) >> 56;
-        let l7 = (self.0 & 0xFF000000000000) >> 48;
-        let l6 = (self.0 & 0xFF0000000000) >> 40;
-        let l5 = (self.0 & 0xFF00000000) >> 32;
-        let l4 = (self.0 & 0xFF000000) >> 24;
-        let l3 = (self.0 & 0xFF0000) >> 16;
-        let l2 = (self.0 & 0xFF00) >> 8;
-        let l1 = self.0 & 0xFF;
-
-        write!(
-            f,
-            "
-        ┌────────┐
-        │{l8:08b}│
-        │{l7:08b}│
-        │{l6:08b}│
-        │{l5:08b}│
-        │{l4:08b}│
-        │{l3:08b}│
-        │{l2:08b}│
-        │{l1:08b}│
-        └────────┘
-        "
-        )
+        writeln!(f)?;
+        for line in self.drawing() {
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
     }
 }
 
@@ -353,3 +400,35 @@ impl BoardRotate for BitBoard {
         self.vertical_flip().diagonal_a1_h8_flip()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_drawing() {
+        let bb = BitBoard::from(&[Square::A1, Square::B1, Square::H8][..]);
+        let text = format!("{bb:?}");
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "");
+        assert_eq!(lines[1], "  ┌────────┐");
+        assert_eq!(lines[2], "8 │.......1│");
+        assert_eq!(lines[9], "1 │11......│");
+        assert_eq!(lines[11], "   abcdefgh");
+
+        let text = side_by_side(&[("rank 1", &RANK_1), ("a-file and h8", &bb)]);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 12);
+        assert_eq!(lines[0], "rank 1        a-file and h8");
+        assert_eq!(lines[2], "8 │........│  8 │.......1│");
+        assert_eq!(lines[9], "1 │11111111│  1 │11......│");
+        assert_eq!(lines[11], "   abcdefgh      abcdefgh");
+
+        // A legend wider than the board widens its column.
+        let text = side_by_side(&[("a-file and h8", &bb), ("rank 1", &RANK_1)]);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "a-file and h8  rank 1");
+        assert_eq!(lines[2], "8 │.......1│   8 │........│");
+        assert_eq!(lines[11], "   abcdefgh       abcdefgh");
+    }
+}
@@ -1,5 +1,10 @@
-use crate::engine::{bitboard::BitBoard, pieces::PieceType};
-use ::std::{fmt::Display, marker::PhantomData};
+use crate::engine::{
+    bitboard::{BitBoard, BoardFlip, BoardRotate, DARK_SQUARES, LIGHT_SQUARES},
+    limits::SearchLimits,
+    moves::Move,
+    pieces::PieceType,
+};
+use ::std::{error::Error, fmt::Display, marker::PhantomData, str::FromStr};
 
 pub struct White;
 pub struct Black;
@@ -18,7 +23,7 @@ pub enum Compass {
     SouthEast = -7,
 }
 
-#[derive(PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum Square {
     A1 = 0,
     B1 = 1,
@@ -86,6 +91,91 @@ pub enum Square {
     H8 = 63,
 }
 
+pub const SQUARES: [Square; 64] = [
+    Square::A1,
+    Square::B1,
+    Square::C1,
+    Square::D1,
+    Square::E1,
+    Square::F1,
+    Square::G1,
+    Square::H1,
+    Square::A2,
+    Square::B2,
+    Square::C2,
+    Square::D2,
+    Square::E2,
+    Square::F2,
+    Square::G2,
+    Square::H2,
+    Square::A3,
+    Square::B3,
+    Square::C3,
+    Square::D3,
+    Square::E3,
+    Square::F3,
+    Square::G3,
+    Square::H3,
+    Square::A4,
+    Square::B4,
+    Square::C4,
+    Square::D4,
+    Square::E4,
+    Square::F4,
+    Square::G4,
+    Square::H4,
+    Square::A5,
+    Square::B5,
+    Square::C5,
+    Square::D5,
+    Square::E5,
+    Square::F5,
+    Square::G5,
+    Square::H5,
+    Square::A6,
+    Square::B6,
+    Square::C6,
+    Square::D6,
+    Square::E6,
+    Square::F6,
+    Square::G6,
+    Square::H6,
+    Square::A7,
+    Square::B7,
+    Square::C7,
+    Square::D7,
+    Square::E7,
+    Square::F7,
+    Square::G7,
+    Square::H7,
+    Square::A8,
+    Square::B8,
+    Square::C8,
+    Square::D8,
+    Square::E8,
+    Square::F8,
+    Square::G8,
+    Square::H8,
+];
+
+impl Square {
+    #[inline]
+    pub fn file(self) -> u8 {
+        self as u8 & 7
+    }
+    #[inline]
+    pub fn rank(self) -> u8 {
+        self as u8 >> 3
+    }
+    /// Number of king steps between two squares.
+    #[inline]
+    pub fn distance(self, other: Square) -> u8 {
+        self.file()
+            .abs_diff(other.file())
+            .max(self.rank().abs_diff(other.rank()))
+    }
+}
+
 //impl Iterator for Square {
 //    type Item = Self;
 //    fn next(&mut self) -> Option<Self::Item> {
@@ -232,6 +322,29 @@ impl Display for Square {
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseSquareError(pub String);
+
+impl Display for ParseSquareError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid square `{}`", self.0)
+    }
+}
+
+impl Error for ParseSquareError {}
+
+impl FromStr for Square {
+    type Err = ParseSquareError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.as_bytes() {
+            &[file @ b'a'..=b'h', rank @ b'1'..=b'8'] => {
+                Ok(SQUARES[((rank - b'1') * 8 + (file - b'a')) as usize])
+            }
+            _ => Err(ParseSquareError(s.to_string())),
+        }
+    }
+}
+
 #[allow(dead_code)]
 pub struct Board<T> {
     // Full board
@@ -262,6 +375,198 @@ pub struct Board<T> {
 }
 
 impl<T> Board<T> {
+    pub fn empty() -> Self {
+        Board {
+            board: BitBoard::default(),
+
+            white_board: BitBoard::default(),
+            black_board: BitBoard::default(),
+
+            white_pawn: (PieceType::WhitePawn, BitBoard::default()),
+            white_knight: (PieceType::WhiteKnight, BitBoard::default()),
+            white_bishop: (PieceType::WhiteBishop, BitBoard::default()),
+            white_rook: (PieceType::WhiteRook, BitBoard::default()),
+            white_queen: (PieceType::WhiteQueen, BitBoard::default()),
+            white_king: (PieceType::WhiteKing, BitBoard::default()),
+
+            black_pawn: (PieceType::BlackPawn, BitBoard::default()),
+            black_knight: (PieceType::BlackKnight, BitBoard::default()),
+            black_bishop: (PieceType::BlackBishop, BitBoard::default()),
+            black_rook: (PieceType::BlackRook, BitBoard::default()),
+            black_queen: (PieceType::BlackQueen, BitBoard::default()),
+            black_king: (PieceType::BlackKing, BitBoard::default()),
+
+            turn: PhantomData,
+        }
+    }
+
+    /// Put `piece` on `square`, replacing whatever stood there.
+    pub fn set_piece(mut self, piece: PieceType, square: Square) -> Self {
+        let mask = BitBoard::from(square);
+        for bb in [
+            &mut self.board,
+            &mut self.white_board,
+            &mut self.black_board,
+            &mut self.white_pawn.1,
+            &mut self.white_knight.1,
+            &mut self.white_bishop.1,
+            &mut self.white_rook.1,
+            &mut self.white_queen.1,
+            &mut self.white_king.1,
+            &mut self.black_pawn.1,
+            &mut self.black_knight.1,
+            &mut self.black_bishop.1,
+            &mut self.black_rook.1,
+            &mut self.black_queen.1,
+            &mut self.black_king.1,
+        ] {
+            *bb -= mask.clone();
+        }
+
+        let piece_board = match piece {
+            PieceType::NoPiece => return self,
+            // White
+            PieceType::WhitePawn => &mut self.white_pawn.1,
+            PieceType::WhiteKnight => &mut self.white_knight.1,
+            PieceType::WhiteBishop => &mut self.white_bishop.1,
+            PieceType::WhiteRook => &mut self.white_rook.1,
+            PieceType::WhiteQueen => &mut self.white_queen.1,
+            PieceType::WhiteKing => &mut self.white_king.1,
+            // Black
+            PieceType::BlackPawn => &mut self.black_pawn.1,
+            PieceType::BlackKnight => &mut self.black_knight.1,
+            PieceType::BlackBishop => &mut self.black_bishop.1,
+            PieceType::BlackRook => &mut self.black_rook.1,
+            PieceType::BlackQueen => &mut self.black_queen.1,
+            PieceType::BlackKing => &mut self.black_king.1,
+        };
+        *piece_board |= mask.clone();
+        if piece.is_white() {
+            self.white_board |= mask.clone();
+        } else {
+            self.black_board |= mask.clone();
+        }
+        self.board |= mask;
+        self
+    }
+
+    /// Squares holding `piece`, or the empty squares for `PieceType::NoPiece`.
+    pub fn pieces(&self, piece: PieceType) -> BitBoard {
+        match piece {
+            PieceType::NoPiece => !self.board.clone(),
+            // White
+            PieceType::WhitePawn => self.white_pawn.1.clone(),
+            PieceType::WhiteKnight => self.white_knight.1.clone(),
+            PieceType::WhiteBishop => self.white_bishop.1.clone(),
+            PieceType::WhiteRook => self.white_rook.1.clone(),
+            PieceType::WhiteQueen => self.white_queen.1.clone(),
+            PieceType::WhiteKing => self.white_king.1.clone(),
+            // Black
+            PieceType::BlackPawn => self.black_pawn.1.clone(),
+            PieceType::BlackKnight => self.black_knight.1.clone(),
+            PieceType::BlackBishop => self.black_bishop.1.clone(),
+            PieceType::BlackRook => self.black_rook.1.clone(),
+            PieceType::BlackQueen => self.black_queen.1.clone(),
+            PieceType::BlackKing => self.black_king.1.clone(),
+        }
+    }
+
+    pub fn white_pieces(&self) -> BitBoard {
+        self.white_board.clone()
+    }
+
+    pub fn black_pieces(&self) -> BitBoard {
+        self.black_board.clone()
+    }
+
+    /// Neither side can ever mate: bare kings, a single minor piece, or only
+    /// bishops that all stand on the same colour.
+    pub fn is_insufficient_material(&self) -> bool {
+        let mating = self.white_pawn.1.clone()
+            | self.white_rook.1.clone()
+            | self.white_queen.1.clone()
+            | self.black_pawn.1.clone()
+            | self.black_rook.1.clone()
+            | self.black_queen.1.clone();
+        if !mating.is_empty() {
+            return false;
+        }
+
+        let knights = self.white_knight.1.clone() | self.black_knight.1.clone();
+        let bishops = self.white_bishop.1.clone() | self.black_bishop.1.clone();
+        if knights.population() + bishops.population() <= 1 {
+            return true;
+        }
+        knights.is_empty()
+            && ((bishops.clone() & LIGHT_SQUARES).is_empty() || (bishops & DARK_SQUARES).is_empty())
+    }
+
+    /// Whether one side could mate by some series of legal moves, however
+    /// unlikely, which decides whether flagging against it loses (FIDE 6.9).
+    ///
+    /// Pawns, rooks, queens, or two minor pieces other than same-coloured
+    /// bishops can always mate. A lone knight needs an enemy pawn, minor piece or rook to
+    /// hem the king in; bishops all on one colour need an enemy pawn, knight
+    /// or bishop on the other colour.
+    pub fn has_mating_material(&self, white: bool) -> bool {
+        let [pawn, knight, bishop, rook, queen] = if white {
+            [
+                PieceType::WhitePawn,
+                PieceType::WhiteKnight,
+                PieceType::WhiteBishop,
+                PieceType::WhiteRook,
+                PieceType::WhiteQueen,
+            ]
+        } else {
+            [
+                PieceType::BlackPawn,
+                PieceType::BlackKnight,
+                PieceType::BlackBishop,
+                PieceType::BlackRook,
+                PieceType::BlackQueen,
+            ]
+        };
+        let [their_pawn, their_knight, their_bishop, their_rook] = if white {
+            [
+                PieceType::BlackPawn,
+                PieceType::BlackKnight,
+                PieceType::BlackBishop,
+                PieceType::BlackRook,
+            ]
+        } else {
+            [
+                PieceType::WhitePawn,
+                PieceType::WhiteKnight,
+                PieceType::WhiteBishop,
+                PieceType::WhiteRook,
+            ]
+        };
+        if !(self.pieces(pawn) | self.pieces(rook) | self.pieces(queen)).is_empty() {
+            return true;
+        }
+
+        let knights = self.pieces(knight).population();
+        let bishops = self.pieces(bishop);
+        let light = !(bishops.clone() & LIGHT_SQUARES).is_empty();
+        let dark = !(bishops & DARK_SQUARES).is_empty();
+        match (knights, light, dark) {
+            (0, false, false) => false,
+            (0, true, false) | (0, false, true) => {
+                let other_colour = if light { DARK_SQUARES } else { LIGHT_SQUARES };
+                !(self.pieces(their_pawn)
+                    | self.pieces(their_knight)
+                    | (self.pieces(their_bishop) & other_colour))
+                    .is_empty()
+            }
+            (1, false, false) => !(self.pieces(their_pawn)
+                | self.pieces(their_knight)
+                | self.pieces(their_bishop)
+                | self.pieces(their_rook))
+            .is_empty(),
+            _ => true,
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         self.board.is_empty()
     }
@@ -305,21 +610,170 @@ impl<T> Board<T> {
     }
 }
 
+impl<T> Board<T> {
+    /// Move the pieces of `mv`, including the rook of a castling move, the
+    /// pawn taken en passant and the promoted piece. Legality is not checked.
+    ///
+    /// Castling is accepted both as the king's two-square move and, as in
+    /// Chess960, as the king taking its own rook.
+    fn apply(self, mv: Move) -> Self {
+        let piece = self.get_piece(mv.from);
+        let target = self.get_piece(mv.to);
+        let rank = mv.from.rank() as usize * 8;
+
+        let castling_rook = match (piece, target) {
+            (PieceType::WhiteKing, PieceType::WhiteRook)
+            | (PieceType::BlackKing, PieceType::BlackRook) => Some(mv.to),
+            (PieceType::WhiteKing | PieceType::BlackKing, _)
+                if mv.from.rank() == mv.to.rank() && mv.from.distance(mv.to) == 2 =>
+            {
+                Some(if mv.to.file() > mv.from.file() {
+                    SQUARES[rank + 7]
+                } else {
+                    SQUARES[rank]
+                })
+            }
+            _ => None,
+        };
+        if let Some(rook_from) = castling_rook {
+            let rook = self.get_piece(rook_from);
+            let (king_to, rook_to) = if rook_from.file() > mv.from.file() {
+                (SQUARES[rank + 6], SQUARES[rank + 5])
+            } else {
+                (SQUARES[rank + 2], SQUARES[rank + 3])
+            };
+            return self
+                .set_piece(PieceType::NoPiece, mv.from)
+                .set_piece(PieceType::NoPiece, rook_from)
+                .set_piece(piece, king_to)
+                .set_piece(rook, rook_to);
+        }
+
+        let en_passant = matches!(piece, PieceType::WhitePawn | PieceType::BlackPawn)
+            && mv.from.file() != mv.to.file()
+            && target == PieceType::NoPiece;
+
+        let board = self
+            .set_piece(PieceType::NoPiece, mv.from)
+            .set_piece(mv.promotion.unwrap_or(piece), mv.to);
+        if en_passant {
+            board.set_piece(PieceType::NoPiece, SQUARES[rank + mv.to.file() as usize])
+        } else {
+            board
+        }
+    }
+
+    /// Hand the move to the other side.
+    fn pass<U>(self) -> Board<U> {
+        Board {
+            board: self.board,
+
+            white_board: self.white_board,
+            black_board: self.black_board,
+
+            white_pawn: self.white_pawn,
+            white_knight: self.white_knight,
+            white_bishop: self.white_bishop,
+            white_rook: self.white_rook,
+            white_queen: self.white_queen,
+            white_king: self.white_king,
+
+            black_pawn: self.black_pawn,
+            black_knight: self.black_knight,
+            black_bishop: self.black_bishop,
+            black_rook: self.black_rook,
+            black_queen: self.black_queen,
+            black_king: self.black_king,
+
+            turn: PhantomData,
+        }
+    }
+}
+
 impl Board<White> {
     pub fn moves(&self) -> BitBoard {
         todo!()
     }
-    pub fn best_move(self) -> Board<Black> {
+    /// Play the best move found within `limits`.
+    pub fn best_move(self, _limits: &SearchLimits) -> Board<Black> {
         todo!()
     }
+    pub fn play(self, mv: Move) -> Board<Black> {
+        self.apply(mv).pass()
+    }
 }
 impl Board<Black> {
     pub fn moves(&self) -> BitBoard {
         todo!()
     }
-    pub fn best_move(self) -> Board<White> {
+    /// Play the best move found within `limits`.
+    pub fn best_move(self, _limits: &SearchLimits) -> Board<White> {
         todo!()
     }
+    pub fn play(self, mv: Move) -> Board<White> {
+        self.apply(mv).pass()
+    }
+}
+
+impl Board<White> {
+    /// Chess960 starting position number `index`, following Scharnagl's
+    /// numbering where 518 is the standard setup.
+    pub fn chess960(index: u16) -> Option<Self> {
+        if index >= 960 {
+            return None;
+        }
+        const KNIGHTS: [(usize, usize); 10] = [
+            (0, 1),
+            (0, 2),
+            (0, 3),
+            (0, 4),
+            (1, 2),
+            (1, 3),
+            (1, 4),
+            (2, 3),
+            (2, 4),
+            (3, 4),
+        ];
+
+        let mut n = index as usize;
+        let mut rank = [PieceType::NoPiece; 8];
+        rank[2 * (n % 4) + 1] = PieceType::WhiteBishop;
+        n /= 4;
+        rank[2 * (n % 4)] = PieceType::WhiteBishop;
+        n /= 4;
+
+        let free = |rank: &[PieceType; 8]| -> Vec<usize> {
+            (0..8).filter(|&f| rank[f] == PieceType::NoPiece).collect()
+        };
+        rank[free(&rank)[n % 6]] = PieceType::WhiteQueen;
+        n /= 6;
+
+        let (first, second) = KNIGHTS[n];
+        let files = free(&rank);
+        rank[files[first]] = PieceType::WhiteKnight;
+        rank[files[second]] = PieceType::WhiteKnight;
+
+        // Rook, king, rook on what is left.
+        for (file, piece) in free(&rank).into_iter().zip([
+            PieceType::WhiteRook,
+            PieceType::WhiteKing,
+            PieceType::WhiteRook,
+        ]) {
+            rank[file] = piece;
+        }
+
+        Some(
+            rank.iter()
+                .enumerate()
+                .fold(Board::empty(), |board, (file, &piece)| {
+                    board
+                        .set_piece(piece, SQUARES[file])
+                        .set_piece(PieceType::WhitePawn, SQUARES[8 + file])
+                        .set_piece(PieceType::BlackPawn, SQUARES[48 + file])
+                        .set_piece(piece.to_black(), SQUARES[56 + file])
+                }),
+        )
+    }
 }
 
 impl Default for Board<White> {
@@ -348,6 +802,59 @@ impl Default for Board<White> {
         }
     }
 }
+impl<T> Board<T> {
+    /// Apply the same square mapping to every bitboard.
+    fn map(self, f: impl Fn(BitBoard) -> BitBoard) -> Self {
+        Board {
+            board: f(self.board),
+
+            white_board: f(self.white_board),
+            black_board: f(self.black_board),
+
+            white_pawn: (self.white_pawn.0, f(self.white_pawn.1)),
+            white_knight: (self.white_knight.0, f(self.white_knight.1)),
+            white_bishop: (self.white_bishop.0, f(self.white_bishop.1)),
+            white_rook: (self.white_rook.0, f(self.white_rook.1)),
+            white_queen: (self.white_queen.0, f(self.white_queen.1)),
+            white_king: (self.white_king.0, f(self.white_king.1)),
+
+            black_pawn: (self.black_pawn.0, f(self.black_pawn.1)),
+            black_knight: (self.black_knight.0, f(self.black_knight.1)),
+            black_bishop: (self.black_bishop.0, f(self.black_bishop.1)),
+            black_rook: (self.black_rook.0, f(self.black_rook.1)),
+            black_queen: (self.black_queen.0, f(self.black_queen.1)),
+            black_king: (self.black_king.0, f(self.black_king.1)),
+
+            turn: PhantomData,
+        }
+    }
+}
+impl<T> BoardFlip for Board<T> {
+    fn vertical_flip(self) -> Self {
+        self.map(BitBoard::vertical_flip)
+    }
+    fn horrizontal_flip(self) -> Self {
+        self.map(BitBoard::horrizontal_flip)
+    }
+    fn diagonal_a1_h8_flip(self) -> Self {
+        self.map(BitBoard::diagonal_a1_h8_flip)
+    }
+    fn diagonal_h1_a8_flip(self) -> Self {
+        self.map(BitBoard::diagonal_h1_a8_flip)
+    }
+}
+impl<T> BoardRotate for Board<T> {
+    fn rotate_90(self) -> Self {
+        self.map(BitBoard::rotate_90)
+    }
+    fn rotate_180(self) -> Self {
+        self.map(BitBoard::rotate_180)
+    }
+    fn rotate_270(self) -> Self {
+        self.map(BitBoard::rotate_270)
+    }
+}
+
 impl<T> Display for Board<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -491,4 +998,184 @@ mod test {
         assert_eq!(board.get_piece(Square::G2), PieceType::WhitePawn);
         assert_eq!(board.get_piece(Square::H2), PieceType::WhitePawn);
     }
+
+    #[test]
+    fn test_symmetries() {
+        let board = Board::<White>::empty()
+            .set_piece(PieceType::WhiteKing, Square::B1)
+            .set_piece(PieceType::BlackRook, Square::C3);
+
+        let flipped = board.vertical_flip();
+        assert_eq!(flipped.get_piece(Square::B8), PieceType::WhiteKing);
+        assert_eq!(flipped.get_piece(Square::C6), PieceType::BlackRook);
+
+        let mirrored = flipped.horrizontal_flip();
+        assert_eq!(mirrored.get_piece(Square::G8), PieceType::WhiteKing);
+        assert_eq!(mirrored.get_piece(Square::F6), PieceType::BlackRook);
+
+        let transposed = mirrored.diagonal_a1_h8_flip();
+        assert_eq!(transposed.get_piece(Square::H7), PieceType::WhiteKing);
+        assert_eq!(transposed.get_piece(Square::F6), PieceType::BlackRook);
+
+        let anti = transposed.diagonal_h1_a8_flip();
+        assert_eq!(anti.get_piece(Square::B1), PieceType::WhiteKing);
+        assert_eq!(anti.get_piece(Square::C3), PieceType::BlackRook);
+        assert_eq!(anti.white_pieces(), BitBoard::from(Square::B1));
+
+        let rotated = anti.rotate_90().rotate_180().rotate_270();
+        assert_eq!(rotated.get_piece(Square::G8), PieceType::WhiteKing);
+        assert_eq!(rotated.get_piece(Square::F6), PieceType::BlackRook);
+    }
+
+    #[test]
+    fn test_parse_square() {
+        for square in SQUARES {
+            assert_eq!(square.to_string().parse(), Ok(square));
+        }
+        assert_eq!(
+            "i1".parse::<Square>(),
+            Err(ParseSquareError("i1".to_string()))
+        );
+        assert!("A1".parse::<Square>().is_err());
+        assert!("a9".parse::<Square>().is_err());
+        assert!("a10".parse::<Square>().is_err());
+    }
+
+    #[test]
+    fn test_insufficient_material() {
+        let kings = Board::<White>::empty()
+            .set_piece(PieceType::WhiteKing, Square::E1)
+            .set_piece(PieceType::BlackKing, Square::E8);
+        assert!(kings.is_insufficient_material());
+        assert!(!Board::default().is_insufficient_material());
+
+        let knight = kings.set_piece(PieceType::BlackKnight, Square::B8);
+        assert!(knight.is_insufficient_material());
+        assert!(!knight.has_mating_material(false));
+        // A white pawn can hem its king in, a queen cannot.
+        let knight = knight.set_piece(PieceType::WhitePawn, Square::A2);
+        assert!(knight.has_mating_material(false));
+        let knight = knight
+            .set_piece(PieceType::NoPiece, Square::A2)
+            .set_piece(PieceType::WhiteQueen, Square::A2);
+        assert!(!knight.has_mating_material(false));
+        let knights = knight
+            .set_piece(PieceType::NoPiece, Square::A2)
+            .set_piece(PieceType::BlackKnight, Square::G8);
+        assert!(!knights.is_insufficient_material());
+        assert!(knights.has_mating_material(false));
+        assert!(!knights.has_mating_material(true));
+
+        let bishops = Board::<White>::empty()
+            .set_piece(PieceType::WhiteKing, Square::E1)
+            .set_piece(PieceType::BlackKing, Square::E8)
+            .set_piece(PieceType::WhiteBishop, Square::C1)
+            .set_piece(PieceType::BlackBishop, Square::F8);
+        assert!(bishops.is_insufficient_material());
+        assert!(!bishops.has_mating_material(true));
+        let opposite = bishops.set_piece(PieceType::BlackBishop, Square::C8);
+        assert!(!opposite.is_insufficient_material());
+        assert!(opposite.has_mating_material(false));
+        // The c8 bishop can block its own king against the c1 bishop.
+        assert!(opposite.has_mating_material(true));
+        let pawn = opposite
+            .set_piece(PieceType::BlackBishop, Square::F8)
+            .set_piece(PieceType::WhitePawn, Square::A2);
+        assert!(!pawn.is_insufficient_material());
+        assert!(pawn.has_mating_material(true));
+    }
+
+    #[test]
+    fn test_play() {
+        let board = Board::default()
+            .play("e2e4".parse().unwrap())
+            .play("d7d5".parse().unwrap());
+        assert_eq!(board.get_piece(Square::E4), PieceType::WhitePawn);
+        assert!(board.has_empty(Square::E2));
+
+        let board = board.play("e4d5".parse().unwrap());
+        assert_eq!(board.get_piece(Square::D5), PieceType::WhitePawn);
+        assert_eq!(board.black_pieces().population(), 15);
+
+        // En passant.
+        let board = board
+            .play("e7e5".parse().unwrap())
+            .play("d5e6".parse().unwrap());
+        assert_eq!(board.get_piece(Square::E6), PieceType::WhitePawn);
+        assert!(board.has_empty(Square::E5));
+        assert_eq!(board.black_pieces().population(), 14);
+
+        // Castling moves the rook too.
+        let board = Board::<White>::empty()
+            .set_piece(PieceType::WhiteKing, Square::E1)
+            .set_piece(PieceType::WhiteRook, Square::H1)
+            .set_piece(PieceType::BlackKing, Square::E8)
+            .set_piece(PieceType::BlackRook, Square::A8)
+            .play("e1g1".parse().unwrap())
+            .play("e8c8".parse().unwrap());
+        assert_eq!(board.get_piece(Square::G1), PieceType::WhiteKing);
+        assert_eq!(board.get_piece(Square::F1), PieceType::WhiteRook);
+        assert_eq!(board.get_piece(Square::C8), PieceType::BlackKing);
+        assert_eq!(board.get_piece(Square::D8), PieceType::BlackRook);
+        assert!(board.has_empty(Square::H1) && board.has_empty(Square::A8));
+
+        // Promotion.
+        let board = board
+            .set_piece(PieceType::WhitePawn, Square::B7)
+            .play("b7b8n".parse().unwrap());
+        assert_eq!(board.get_piece(Square::B8), PieceType::WhiteKnight);
+    }
+
+    #[test]
+    fn test_chess960() {
+        let standard = Board::chess960(518).unwrap();
+        let default = Board::default();
+        for square in SQUARES {
+            assert_eq!(standard.get_piece(square), default.get_piece(square));
+        }
+
+        // Scharnagl's first position: BBQNNRKR.
+        let first = Board::chess960(0).unwrap();
+        assert_eq!(first.get_piece(Square::A1), PieceType::WhiteBishop);
+        assert_eq!(first.get_piece(Square::B1), PieceType::WhiteBishop);
+        assert_eq!(first.get_piece(Square::C1), PieceType::WhiteQueen);
+        assert_eq!(first.get_piece(Square::G1), PieceType::WhiteKing);
+        assert_eq!(first.get_piece(Square::H8), PieceType::BlackRook);
+        assert!(Board::chess960(960).is_none());
+
+        for index in 0..960 {
+            let board = Board::chess960(index).unwrap();
+            let bishops = board.pieces(PieceType::WhiteBishop);
+            assert_eq!((bishops.clone() & LIGHT_SQUARES).population(), 1);
+            assert_eq!((bishops & DARK_SQUARES).population(), 1);
+
+            let king = board.pieces(PieceType::WhiteKing).trailing_zeros();
+            let rooks: Vec<u32> = board
+                .pieces(PieceType::WhiteRook)
+                .squares()
+                .map(|square| square as u32)
+                .collect();
+            assert!(rooks[0] < king && king < rooks[1]);
+            assert_eq!(board.white_pieces().population(), 16);
+        }
+    }
+
+    #[test]
+    fn test_chess960_castling() {
+        // King b1 takes its own rook on a1 to castle queen side.
+        let board = Board::<White>::empty()
+            .set_piece(PieceType::WhiteRook, Square::A1)
+            .set_piece(PieceType::WhiteKing, Square::B1)
+            .set_piece(PieceType::WhiteRook, Square::H1)
+            .set_piece(PieceType::BlackKing, Square::G8)
+            .set_piece(PieceType::BlackRook, Square::H8)
+            .play("b1a1".parse().unwrap())
+            .play("g8h8".parse().unwrap());
+        assert_eq!(board.get_piece(Square::C1), PieceType::WhiteKing);
+        assert_eq!(board.get_piece(Square::D1), PieceType::WhiteRook);
+        assert!(board.has_empty(Square::A1) && board.has_empty(Square::B1));
+        assert_eq!(board.get_piece(Square::H1), PieceType::WhiteRook);
+        assert_eq!(board.get_piece(Square::G8), PieceType::BlackKing);
+        assert_eq!(board.get_piece(Square::F8), PieceType::BlackRook);
+    }
 }
@@ -0,0 +1,239 @@
+use super::{
+    bitboard::{BitBoard, EMPTY},
+    board::{Board, SQUARES, Square},
+    pieces::PieceType,
+};
+use ::std::{error::Error, fmt::Display};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ParseCastlingError {
+    InvalidCharacter(char),
+    /// No king on its first rank to castle with.
+    MissingKing(char),
+    /// No rook where the field says one may castle.
+    MissingRook(char),
+}
+
+impl Display for ParseCastlingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidCharacter(c) => write!(f, "invalid castling character `{c}`"),
+            Self::MissingKing(c) => write!(f, "no king to castle with for `{c}`"),
+            Self::MissingRook(c) => write!(f, "no rook to castle with for `{c}`"),
+        }
+    }
+}
+
+impl Error for ParseCastlingError {}
+
+/// Files of the rooks each side may still castle with, king side first.
+///
+/// Storing rook files rather than flags covers Chess960, where the rooks
+/// may start on any file.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct CastlingRights {
+    pub white: [Option<u8>; 2],
+    pub black: [Option<u8>; 2],
+}
+
+impl CastlingRights {
+    /// Parse a FEN castling field in standard (`KQkq`), Shredder-FEN
+    /// (`HAha`) or X-FEN form, where `K`/`Q` mean the outermost rook.
+    pub fn parse<T>(field: &str, board: &Board<T>) -> Result<Self, ParseCastlingError> {
+        let mut rights = CastlingRights::default();
+        if field == "-" {
+            return Ok(rights);
+        }
+
+        for c in field.chars() {
+            let white = c.is_ascii_uppercase();
+            let (king, rook, rank) = if white {
+                (PieceType::WhiteKing, PieceType::WhiteRook, 0)
+            } else {
+                (PieceType::BlackKing, PieceType::BlackRook, 7)
+            };
+            let king_file = (0..8)
+                .find(|&file| board.get_piece(SQUARES[rank * 8 + file as usize]) == king)
+                .ok_or(ParseCastlingError::MissingKing(c))?;
+            let rook_on = |file: u8| board.get_piece(SQUARES[rank * 8 + file as usize]) == rook;
+
+            let rook_file = match c.to_ascii_lowercase() {
+                'k' => (king_file + 1..8).rev().find(|&file| rook_on(file)),
+                'q' => (0..king_file).find(|&file| rook_on(file)),
+                file @ 'a'..='h' => Some(file as u8 - b'a').filter(|&file| rook_on(file)),
+                _ => return Err(ParseCastlingError::InvalidCharacter(c)),
+            }
+            .ok_or(ParseCastlingError::MissingRook(c))?;
+
+            let side = if rook_file > king_file { 0 } else { 1 };
+            if white {
+                rights.white[side] = Some(rook_file);
+            } else {
+                rights.black[side] = Some(rook_file);
+            }
+        }
+        Ok(rights)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == CastlingRights::default()
+    }
+
+    /// X-FEN field: `KQkq` letters unless another rook stands further out
+    /// than the castling rook, in which case the rook's file is given.
+    pub fn to_xfen<T>(&self, board: &Board<T>) -> String {
+        if self.is_empty() {
+            return "-".to_string();
+        }
+        let mut field = String::new();
+        for (rights, rook, rank, letters) in [
+            (self.white, PieceType::WhiteRook, 0, ['K', 'Q']),
+            (self.black, PieceType::BlackRook, 7, ['k', 'q']),
+        ] {
+            for (side, letter) in letters.into_iter().enumerate() {
+                let Some(file) = rights[side] else {
+                    continue;
+                };
+                let outer: Vec<u8> = if side == 0 {
+                    (file + 1..8).collect()
+                } else {
+                    (0..file).collect()
+                };
+                let hidden = outer
+                    .into_iter()
+                    .any(|f| board.get_piece(SQUARES[rank * 8 + f as usize]) == rook);
+                field.push(if hidden {
+                    file_letter(file, letter.is_ascii_uppercase())
+                } else {
+                    letter
+                });
+            }
+        }
+        field
+    }
+}
+
+fn file_letter(file: u8, white: bool) -> char {
+    let letter = (b'a' + file) as char;
+    if white {
+        letter.to_ascii_uppercase()
+    } else {
+        letter
+    }
+}
+
+impl Display for CastlingRights {
+    /// Shredder-FEN field, naming the rook files: `HAha`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "-");
+        }
+        for file in self.white.iter().flatten() {
+            write!(f, "{}", file_letter(*file, true))?;
+        }
+        for file in self.black.iter().flatten() {
+            write!(f, "{}", file_letter(*file, false))?;
+        }
+        Ok(())
+    }
+}
+
+/// Squares from `from` to `to` on one rank, both included.
+fn span(from: Square, to: Square) -> BitBoard {
+    let rank = from.rank() as usize * 8;
+    let (low, high) = (from.file().min(to.file()), from.file().max(to.file()));
+    (low..=high).fold(EMPTY, |bb, file| bb.set(SQUARES[rank + file as usize]))
+}
+
+/// Generalised castling rule for a king and rook on the same rank: the king
+/// ends on the g- or c-file and the rook next to it on the f- or d-file.
+///
+/// Returns the squares that must be empty, apart from the king and rook
+/// themselves, and the squares the king crosses that must not be attacked.
+pub fn castling_squares(king: Square, rook: Square) -> (BitBoard, BitBoard) {
+    let rank = king.rank() as usize * 8;
+    let (king_to, rook_to) = if rook.file() > king.file() {
+        (SQUARES[rank + 6], SQUARES[rank + 5])
+    } else {
+        (SQUARES[rank + 2], SQUARES[rank + 3])
+    };
+
+    let empty = (span(king, king_to) | span(rook, rook_to))
+        .unset(king)
+        .unset(rook);
+    (empty, span(king, king_to))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::engine::board::White;
+
+    #[test]
+    fn test_parse() {
+        let board = Board::default();
+        let rights = CastlingRights::parse("KQkq", &board).unwrap();
+        assert_eq!(rights.white, [Some(7), Some(0)]);
+        assert_eq!(rights.black, [Some(7), Some(0)]);
+        assert_eq!(rights.to_string(), "HAha");
+        assert_eq!(rights.to_xfen(&board), "KQkq");
+        assert_eq!(CastlingRights::parse("HAha", &board), Ok(rights));
+
+        let none = CastlingRights::parse("-", &board).unwrap();
+        assert!(none.is_empty());
+        assert_eq!(none.to_string(), "-");
+
+        assert_eq!(
+            CastlingRights::parse("Kx", &board),
+            Err(ParseCastlingError::InvalidCharacter('x'))
+        );
+        assert_eq!(
+            CastlingRights::parse("B", &board),
+            Err(ParseCastlingError::MissingRook('B'))
+        );
+        assert_eq!(
+            CastlingRights::parse("K", &Board::<White>::empty()),
+            Err(ParseCastlingError::MissingKing('K'))
+        );
+    }
+
+    #[test]
+    fn test_inner_rook() {
+        // Rooks on a1, d1 and h1 around a king on e1 (not a starting
+        // position, but legal X-FEN): castling with the d1 rook needs its file.
+        let board = Board::<White>::empty()
+            .set_piece(PieceType::WhiteRook, Square::A1)
+            .set_piece(PieceType::WhiteRook, Square::D1)
+            .set_piece(PieceType::WhiteKing, Square::E1)
+            .set_piece(PieceType::WhiteRook, Square::H1)
+            .set_piece(PieceType::BlackKing, Square::E8);
+        let rights = CastlingRights::parse("KD", &board).unwrap();
+        assert_eq!(rights.white, [Some(7), Some(3)]);
+        assert_eq!(rights.to_xfen(&board), "KD");
+        assert_eq!(
+            CastlingRights::parse("Q", &board).unwrap().white[1],
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn test_castling_squares() {
+        // Standard king-side castling.
+        let (empty, safe) = castling_squares(Square::E1, Square::H1);
+        assert_eq!(empty, BitBoard::from(&[Square::F1, Square::G1][..]));
+        assert_eq!(
+            safe,
+            BitBoard::from(&[Square::E1, Square::F1, Square::G1][..])
+        );
+
+        // Chess960: king b1, rook a1 castling queen side.
+        let (empty, safe) = castling_squares(Square::B1, Square::A1);
+        assert_eq!(empty, BitBoard::from(&[Square::C1, Square::D1][..]));
+        assert_eq!(safe, BitBoard::from(&[Square::B1, Square::C1][..]));
+
+        // King already on g8 with the rook on h8: only f8 must be free.
+        let (empty, safe) = castling_squares(Square::G8, Square::H8);
+        assert_eq!(empty, BitBoard::from(Square::F8));
+        assert_eq!(safe, BitBoard::from(Square::G8));
+    }
+}
@@ -0,0 +1,366 @@
+use super::{
+    bitboard::{BitBoard, DARK_SQUARES, LIGHT_SQUARES},
+    board::{Board, SQUARES, Square},
+    kpk,
+    pieces::PieceType,
+};
+
+/// Score of a position that is won with correct play, before material and mop-up bonuses.
+pub const KNOWN_WIN: i32 = 10_000;
+pub const DRAW: i32 = 0;
+
+/// Scale factors are out of `SCALE_NORMAL`.
+pub const SCALE_NORMAL: u8 = 64;
+pub const SCALE_DRAW: u8 = 0;
+
+#[derive(Default, PartialEq, Eq)]
+struct Material {
+    pawns: u32,
+    knights: u32,
+    bishops: u32,
+    rooks: u32,
+    queens: u32,
+}
+
+impl Material {
+    fn of<T>(board: &Board<T>, white: bool) -> Self {
+        let count = |white_piece: PieceType, black_piece: PieceType| {
+            board
+                .pieces(if white { white_piece } else { black_piece })
+                .population()
+        };
+        Material {
+            pawns: count(PieceType::WhitePawn, PieceType::BlackPawn),
+            knights: count(PieceType::WhiteKnight, PieceType::BlackKnight),
+            bishops: count(PieceType::WhiteBishop, PieceType::BlackBishop),
+            rooks: count(PieceType::WhiteRook, PieceType::BlackRook),
+            queens: count(PieceType::WhiteQueen, PieceType::BlackQueen),
+        }
+    }
+
+    fn is_bare_king(&self) -> bool {
+        *self == Material::default()
+    }
+
+    fn is(&self, knights: u32, bishops: u32, rooks: u32, queens: u32) -> bool {
+        self.pawns == 0
+            && self.knights == knights
+            && self.bishops == bishops
+            && self.rooks == rooks
+            && self.queens == queens
+    }
+
+    fn non_pawn(&self) -> i32 {
+        self.knights as i32 * PieceType::WhiteKnight.value()
+            + self.bishops as i32 * PieceType::WhiteBishop.value()
+            + self.rooks as i32 * PieceType::WhiteRook.value()
+            + self.queens as i32 * PieceType::WhiteQueen.value()
+    }
+}
+
+/// Lowest set square, `None` when `bb` is empty.
+fn square_of(bb: BitBoard) -> Option<Square> {
+    (!bb.is_empty()).then(|| SQUARES[bb.trailing_zeros() as usize])
+}
+
+/// Bonus for driving a king to the edge of the board.
+fn push_to_edge(square: Square) -> i32 {
+    let file = square.file().min(7 - square.file()) as i32;
+    let rank = square.rank().min(7 - square.rank()) as i32;
+    20 * (6 - file - rank)
+}
+
+/// Bonus for bringing two kings together.
+fn push_close(a: Square, b: Square) -> i32 {
+    140 - 20 * a.distance(b) as i32
+}
+
+/// Mate with a major piece: drive the lone king to any edge.
+fn mate_with_major(strong: &Material, strong_king: Square, weak_king: Square) -> i32 {
+    KNOWN_WIN + strong.non_pawn() + push_to_edge(weak_king) + push_close(strong_king, weak_king)
+}
+
+/// Bishop and knight mate: only the corners of the bishop's colour can be forced.
+fn mate_with_bishop_and_knight(
+    strong: &Material,
+    strong_king: Square,
+    weak_king: Square,
+    bishop: BitBoard,
+) -> i32 {
+    let corners = if DARK_SQUARES.check(bishop) {
+        [Square::A1, Square::H8]
+    } else {
+        [Square::A8, Square::H1]
+    };
+    let corner_distance = corners
+        .into_iter()
+        .map(|corner| weak_king.distance(corner) as i32)
+        .min()
+        .unwrap_or_default();
+
+    KNOWN_WIN + strong.non_pawn() + 40 * (7 - corner_distance) + push_close(strong_king, weak_king)
+}
+
+/// Exact score from the KPK bitbase, with the strong side playing white.
+fn king_and_pawn(
+    strong_king: Square,
+    pawn: Square,
+    weak_king: Square,
+    strong_to_move: bool,
+) -> i32 {
+    if kpk::probe(strong_king, pawn, weak_king, strong_to_move) {
+        KNOWN_WIN + PieceType::WhitePawn.value() + pawn.rank() as i32
+    } else {
+        DRAW
+    }
+}
+
+/// Score, from white's point of view, of the endgames that have dedicated knowledge.
+///
+/// Returns `None` when the material on the board has no specialised evaluator,
+/// or when the position is impossible: a king missing, or a pawn on a back rank.
+pub fn evaluate<T>(board: &Board<T>, white_to_move: bool) -> Option<i32> {
+    let white = Material::of(board, true);
+    let black = Material::of(board, false);
+    let white_king = square_of(board.pieces(PieceType::WhiteKing))?;
+    let black_king = square_of(board.pieces(PieceType::BlackKing))?;
+
+    let (strong_is_white, strong, weak) = if black.is_bare_king() {
+        (true, &white, &black)
+    } else if white.is_bare_king() {
+        (false, &black, &white)
+    } else {
+        return None;
+    };
+    let (strong_king, weak_king) = if strong_is_white {
+        (white_king, black_king)
+    } else {
+        (black_king, white_king)
+    };
+    let strong_pieces = |white_piece, black_piece| {
+        board.pieces(if strong_is_white {
+            white_piece
+        } else {
+            black_piece
+        })
+    };
+
+    let score = if *strong
+        == (Material {
+            pawns: 1,
+            ..Material::default()
+        }) {
+        let pawn = square_of(strong_pieces(PieceType::WhitePawn, PieceType::BlackPawn))?;
+        // A pawn on either back rank is not a position the bitbase covers.
+        if matches!(pawn.rank(), 0 | 7) {
+            return None;
+        }
+        if strong_is_white {
+            king_and_pawn(strong_king, pawn, weak_king, white_to_move)
+        } else {
+            // Look at the board from black's side so the pawn walks up.
+            let flip = |square: Square| SQUARES[square as usize ^ 56];
+            king_and_pawn(
+                flip(strong_king),
+                flip(pawn),
+                flip(weak_king),
+                !white_to_move,
+            )
+        }
+    } else if strong.is(0, 0, 0, 0)
+        || strong.is(1, 0, 0, 0)
+        || strong.is(0, 1, 0, 0)
+        || strong.is(2, 0, 0, 0)
+    {
+        // Bare kings, a lone minor piece, or two knights cannot force mate.
+        DRAW
+    } else if strong.is(1, 1, 0, 0) {
+        let bishop = strong_pieces(PieceType::WhiteBishop, PieceType::BlackBishop);
+        mate_with_bishop_and_knight(strong, strong_king, weak_king, bishop)
+    } else if strong.is(0, 0, 1, 0) || strong.is(0, 0, 0, 1) {
+        mate_with_major(strong, strong_king, weak_king)
+    } else {
+        return None;
+    };
+
+    debug_assert!(weak.is_bare_king());
+    Some(if strong_is_white { score } else { -score })
+}
+
+/// Factor, out of `SCALE_NORMAL`, by which to scale an advantage held by the
+/// `white` (or black) side when the material is known to be drawish.
+pub fn scale_factor<T>(board: &Board<T>, white: bool) -> u8 {
+    let strong = Material::of(board, white);
+    let weak = Material::of(board, !white);
+
+    // Two knights cannot force mate against a bare king.
+    if strong.is(2, 0, 0, 0) && weak.is_bare_king() {
+        return SCALE_DRAW;
+    }
+
+    // Opposite-coloured bishops with nothing but pawns besides.
+    let bishop_only =
+        |m: &Material| m.knights == 0 && m.bishops == 1 && m.rooks == 0 && m.queens == 0;
+    if bishop_only(&strong) && bishop_only(&weak) {
+        let bishops = board.pieces(PieceType::WhiteBishop) | board.pieces(PieceType::BlackBishop);
+        let light = (bishops.clone() & LIGHT_SQUARES).population();
+        let dark = (bishops & DARK_SQUARES).population();
+        if light == 1 && dark == 1 {
+            return if strong.pawns == 0 && weak.pawns == 0 {
+                SCALE_DRAW
+            } else {
+                SCALE_NORMAL / 4
+            };
+        }
+    }
+
+    // Without pawns, being a minor piece up or less is rarely enough.
+    if strong.pawns == 0 && strong.non_pawn() - weak.non_pawn() <= PieceType::WhiteBishop.value() {
+        return if strong.non_pawn() < PieceType::WhiteRook.value() {
+            SCALE_DRAW
+        } else if weak.non_pawn() <= PieceType::WhiteBishop.value() {
+            4
+        } else {
+            14
+        };
+    }
+
+    SCALE_NORMAL
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::engine::board::White;
+
+    fn board(pieces: &[(PieceType, Square)]) -> Board<White> {
+        pieces
+            .iter()
+            .fold(Board::empty(), |board, &(piece, square)| {
+                board.set_piece(piece, square)
+            })
+    }
+
+    #[test]
+    fn test_king_and_pawn() {
+        let won = board(&[
+            (PieceType::WhiteKing, Square::E6),
+            (PieceType::WhitePawn, Square::E5),
+            (PieceType::BlackKing, Square::E8),
+        ]);
+        assert!(evaluate(&won, true).unwrap() > KNOWN_WIN);
+
+        let corner = board(&[
+            (PieceType::WhiteKing, Square::E2),
+            (PieceType::WhitePawn, Square::H3),
+            (PieceType::BlackKing, Square::H8),
+        ]);
+        assert_eq!(evaluate(&corner, true), Some(DRAW));
+
+        let black_won = board(&[
+            (PieceType::BlackKing, Square::D3),
+            (PieceType::BlackPawn, Square::D4),
+            (PieceType::WhiteKing, Square::D1),
+        ]);
+        assert!(evaluate(&black_won, false).unwrap() < -KNOWN_WIN);
+    }
+
+    #[test]
+    fn test_mates() {
+        let edge = board(&[
+            (PieceType::WhiteKing, Square::C3),
+            (PieceType::WhiteRook, Square::H7),
+            (PieceType::BlackKing, Square::A1),
+        ]);
+        let centre = board(&[
+            (PieceType::WhiteKing, Square::C3),
+            (PieceType::WhiteRook, Square::H7),
+            (PieceType::BlackKing, Square::E5),
+        ]);
+        assert!(evaluate(&edge, true).unwrap() > evaluate(&centre, true).unwrap());
+        assert!(evaluate(&centre, true).unwrap() > KNOWN_WIN);
+
+        let queen = board(&[
+            (PieceType::WhiteKing, Square::C3),
+            (PieceType::BlackQueen, Square::H7),
+            (PieceType::BlackKing, Square::E5),
+        ]);
+        assert!(evaluate(&queen, true).unwrap() < -KNOWN_WIN);
+
+        // Dark-squared bishop: a1 and h8 are the mating corners.
+        let right = board(&[
+            (PieceType::WhiteKing, Square::C3),
+            (PieceType::WhiteBishop, Square::C1),
+            (PieceType::WhiteKnight, Square::E4),
+            (PieceType::BlackKing, Square::A1),
+        ]);
+        let wrong = board(&[
+            (PieceType::WhiteKing, Square::C6),
+            (PieceType::WhiteBishop, Square::C1),
+            (PieceType::WhiteKnight, Square::E4),
+            (PieceType::BlackKing, Square::A8),
+        ]);
+        assert!(evaluate(&right, true).unwrap() > evaluate(&wrong, true).unwrap());
+    }
+
+    #[test]
+    fn test_drawish() {
+        let knights = board(&[
+            (PieceType::WhiteKing, Square::C3),
+            (PieceType::WhiteKnight, Square::D4),
+            (PieceType::WhiteKnight, Square::E4),
+            (PieceType::BlackKing, Square::E8),
+        ]);
+        assert_eq!(evaluate(&knights, true), Some(DRAW));
+        assert_eq!(scale_factor(&knights, true), SCALE_DRAW);
+
+        let opposite = board(&[
+            (PieceType::WhiteKing, Square::C3),
+            (PieceType::WhiteBishop, Square::C1),
+            (PieceType::WhitePawn, Square::A4),
+            (PieceType::WhitePawn, Square::B4),
+            (PieceType::BlackKing, Square::E8),
+            (PieceType::BlackBishop, Square::C8),
+            (PieceType::BlackPawn, Square::H7),
+        ]);
+        assert_eq!(evaluate(&opposite, true), None);
+        assert_eq!(scale_factor(&opposite, true), SCALE_NORMAL / 4);
+
+        let same = opposite
+            .set_piece(PieceType::BlackBishop, Square::F8)
+            .set_piece(PieceType::NoPiece, Square::C8);
+        assert_eq!(scale_factor(&same, true), SCALE_NORMAL);
+    }
+
+    #[test]
+    fn test_missing_king() {
+        let kingless = board(&[
+            (PieceType::WhiteKing, Square::C3),
+            (PieceType::WhiteRook, Square::D4),
+        ]);
+        assert_eq!(evaluate(&kingless, true), None);
+        assert_eq!(evaluate(&Board::<White>::empty(), false), None);
+
+        for pawn in [Square::A8, Square::A1] {
+            for (strong_pawn, strong_king, weak_king) in [
+                (
+                    PieceType::WhitePawn,
+                    PieceType::WhiteKing,
+                    PieceType::BlackKing,
+                ),
+                (
+                    PieceType::BlackPawn,
+                    PieceType::BlackKing,
+                    PieceType::WhiteKing,
+                ),
+            ] {
+                let back_rank = board(&[
+                    (strong_king, Square::C3),
+                    (strong_pawn, pawn),
+                    (weak_king, Square::H5),
+                ]);
+                assert_eq!(evaluate(&back_rank, true), None);
+            }
+        }
+    }
+}
@@ -0,0 +1,178 @@
+/// Number of reversible half-moves after which either side may claim a draw.
+pub const FIFTY_MOVE_PLIES: u32 = 100;
+
+struct Entry {
+    key: u64,
+    /// Reversible half-moves played before reaching this position.
+    halfmove_clock: u32,
+    /// Half-moves since the last move no earlier position can repeat across.
+    repeatable_plies: u32,
+}
+
+/// Zobrist keys of the positions reached so far, for repetition and
+/// fifty-move rule detection.
+pub struct History {
+    entries: Vec<Entry>,
+}
+
+impl History {
+    /// Start from a position whose halfmove clock is already `halfmove_clock`.
+    pub fn new(key: u64, halfmove_clock: u32) -> Self {
+        History {
+            entries: vec![Entry {
+                key,
+                halfmove_clock,
+                repeatable_plies: 0,
+            }],
+        }
+    }
+
+    /// Record the position reached by a move. Captures and pawn moves are
+    /// irreversible: no earlier position can repeat after them.
+    pub fn push(&mut self, key: u64, irreversible: bool) {
+        let (halfmove_clock, repeatable_plies) = if irreversible {
+            (0, 0)
+        } else {
+            (
+                self.halfmove_clock() + 1,
+                self.current().repeatable_plies + 1,
+            )
+        };
+        self.entries.push(Entry {
+            key,
+            halfmove_clock,
+            repeatable_plies,
+        });
+    }
+
+    /// Record a move that keeps the fifty-move count running but after which
+    /// no earlier position can repeat, because the castling or en passant
+    /// rights the key leaves out have changed.
+    pub fn push_boundary(&mut self, key: u64) {
+        self.entries.push(Entry {
+            key,
+            halfmove_clock: self.halfmove_clock() + 1,
+            repeatable_plies: 0,
+        });
+    }
+
+    /// Forget the last move. The starting position is never popped.
+    pub fn pop(&mut self) {
+        if self.entries.len() > 1 {
+            self.entries.pop();
+        }
+    }
+
+    pub fn key(&self) -> u64 {
+        self.current().key
+    }
+
+    pub fn halfmove_clock(&self) -> u32 {
+        self.current().halfmove_clock
+    }
+
+    fn current(&self) -> &Entry {
+        self.entries
+            .last()
+            .expect("history always holds the starting position")
+    }
+
+    /// Earlier occurrences of the current position. Only positions since the
+    /// last irreversible move or boundary, with the same side to move, are
+    /// compared.
+    pub fn repetitions(&self) -> usize {
+        let current = self.current();
+        self.entries
+            .iter()
+            .rev()
+            .take(current.repeatable_plies as usize + 1)
+            .skip(2)
+            .step_by(2)
+            .filter(|entry| entry.key == current.key)
+            .count()
+    }
+
+    /// Twofold repetition, enough for search to score the position as a draw.
+    pub fn is_repetition(&self) -> bool {
+        self.repetitions() >= 1
+    }
+
+    /// Threefold repetition, ending the game.
+    pub fn is_threefold(&self) -> bool {
+        self.repetitions() >= 2
+    }
+
+    /// Fifty moves by each side without a capture or pawn move. A checkmate
+    /// delivered on the last move still takes precedence.
+    pub fn is_fifty_moves(&self) -> bool {
+        self.halfmove_clock() >= FIFTY_MOVE_PLIES
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_repetition() {
+        // Knights shuffling out and back: positions 1, 2, 3, 4, 1, 2, 3, 4, 1.
+        let mut history = History::new(1, 0);
+        for key in [2, 3, 4, 1] {
+            history.push(key, false);
+        }
+        assert!(history.is_repetition());
+        assert!(!history.is_threefold());
+
+        for key in [2, 3, 4, 1] {
+            history.push(key, false);
+        }
+        assert!(history.is_threefold());
+        assert_eq!(history.repetitions(), 2);
+
+        history.pop();
+        assert_eq!(history.key(), 4);
+        assert!(history.is_repetition());
+        assert!(!history.is_threefold());
+
+        // A pawn move makes every earlier position unreachable.
+        history.push(1, true);
+        assert!(!history.is_repetition());
+    }
+
+    #[test]
+    fn test_boundary() {
+        // The first 1 still had castling rights, the later ones do not.
+        let mut history = History::new(1, 0);
+        history.push_boundary(2);
+        for key in [3, 4, 1, 2, 3, 4, 1] {
+            history.push(key, false);
+        }
+        assert_eq!(history.repetitions(), 1);
+        assert_eq!(history.halfmove_clock(), 8);
+    }
+
+    #[test]
+    fn test_side_to_move() {
+        // The same key an odd number of plies apart is not a repetition.
+        let mut history = History::new(1, 0);
+        history.push(1, false);
+        assert!(!history.is_repetition());
+    }
+
+    #[test]
+    fn test_fifty_moves() {
+        let mut history = History::new(0, 90);
+        for key in 1..10 {
+            history.push(key, false);
+        }
+        assert_eq!(history.halfmove_clock(), 99);
+        assert!(!history.is_fifty_moves());
+
+        history.push(10, false);
+        assert!(history.is_fifty_moves());
+
+        history.push(11, true);
+        assert_eq!(history.halfmove_clock(), 0);
+        assert!(!history.is_fifty_moves());
+    }
+}
@@ -0,0 +1,226 @@
+use super::board::{SQUARES, Square};
+use ::std::sync::OnceLock;
+
+/// Side to move (1 bit), black king (6 bits), white king (6 bits), pawn file
+/// a-d (2 bits) and pawn rank 7-2 (3 bits).
+const MAX_INDEX: usize = 2 * 24 * 64 * 64;
+
+static BITBASE: OnceLock<Vec<u64>> = OnceLock::new();
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Invalid,
+    Unknown,
+    Draw,
+    Win,
+}
+
+#[derive(Clone, Copy)]
+struct Position {
+    white_to_move: bool,
+    white_king: Square,
+    black_king: Square,
+    pawn: Square,
+}
+
+fn index(white_to_move: bool, white_king: Square, black_king: Square, pawn: Square) -> usize {
+    !white_to_move as usize
+        | (black_king as usize) << 1
+        | (white_king as usize) << 7
+        | (pawn.file() as usize) << 13
+        | (6 - pawn.rank() as usize) << 15
+}
+
+impl Position {
+    fn from_index(idx: usize) -> Self {
+        Position {
+            white_to_move: idx & 1 == 0,
+            black_king: SQUARES[(idx >> 1) & 0x3F],
+            white_king: SQUARES[(idx >> 7) & 0x3F],
+            pawn: SQUARES[(6 - (idx >> 15)) * 8 + ((idx >> 13) & 0x3)],
+        }
+    }
+
+    fn pawn_attacks(&self, square: Square) -> bool {
+        square.rank() == self.pawn.rank() + 1 && square.file().abs_diff(self.pawn.file()) == 1
+    }
+
+    fn king_steps(square: Square) -> impl Iterator<Item = Square> {
+        SQUARES
+            .into_iter()
+            .filter(move |&to| square.distance(to) == 1)
+    }
+
+    fn outcome(&self) -> Outcome {
+        let push = SQUARES[self.pawn as usize + 8];
+
+        if self.white_king == self.black_king
+            || self.white_king == self.pawn
+            || self.black_king == self.pawn
+            || self.white_king.distance(self.black_king) <= 1
+            || (self.white_to_move && self.pawn_attacks(self.black_king))
+        {
+            return Outcome::Invalid;
+        }
+
+        // Immediate promotion that the black king cannot answer by capturing the new queen.
+        if self.white_to_move
+            && self.pawn.rank() == 6
+            && self.white_king != push
+            && (self.black_king.distance(push) > 1 || self.white_king.distance(push) == 1)
+        {
+            return Outcome::Win;
+        }
+
+        if !self.white_to_move {
+            let stalemate = Self::king_steps(self.black_king)
+                .all(|to| self.white_king.distance(to) <= 1 || self.pawn_attacks(to));
+            let loose_pawn =
+                self.black_king.distance(self.pawn) == 1 && self.white_king.distance(self.pawn) > 1;
+            if stalemate || loose_pawn {
+                return Outcome::Draw;
+            }
+        }
+
+        Outcome::Unknown
+    }
+
+    fn classify(&self, table: &[Outcome]) -> Outcome {
+        let mut win = false;
+        let mut draw = false;
+        let mut unknown = false;
+        let mut record = |outcome: Outcome| match outcome {
+            Outcome::Win => win = true,
+            Outcome::Draw => draw = true,
+            Outcome::Unknown => unknown = true,
+            Outcome::Invalid => {}
+        };
+
+        if self.white_to_move {
+            for to in Self::king_steps(self.white_king)
+                .filter(|&to| to != self.pawn && to.distance(self.black_king) > 1)
+            {
+                record(table[index(false, to, self.black_king, self.pawn)]);
+            }
+
+            let push = SQUARES[self.pawn as usize + 8];
+            if self.pawn.rank() < 6 && push != self.white_king && push != self.black_king {
+                record(table[index(false, self.white_king, self.black_king, push)]);
+
+                let double = SQUARES[push as usize + 8];
+                if self.pawn.rank() == 1 && double != self.white_king && double != self.black_king {
+                    record(table[index(false, self.white_king, self.black_king, double)]);
+                }
+            }
+
+            if win {
+                Outcome::Win
+            } else if unknown {
+                Outcome::Unknown
+            } else {
+                Outcome::Draw
+            }
+        } else {
+            for to in Self::king_steps(self.black_king)
+                .filter(|&to| to.distance(self.white_king) > 1 && !self.pawn_attacks(to))
+            {
+                record(table[index(true, self.white_king, to, self.pawn)]);
+            }
+
+            if draw {
+                Outcome::Draw
+            } else if unknown {
+                Outcome::Unknown
+            } else {
+                Outcome::Win
+            }
+        }
+    }
+}
+
+/// Build the king+pawn vs king bitbase by retrograde analysis: start from the
+/// positions that are decided on the spot and propagate results backwards
+/// until nothing changes.
+fn generate() -> Vec<u64> {
+    let positions: Vec<Position> = (0..MAX_INDEX).map(Position::from_index).collect();
+    let mut table: Vec<Outcome> = positions.iter().map(Position::outcome).collect();
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (idx, position) in positions.iter().enumerate() {
+            if table[idx] == Outcome::Unknown {
+                let outcome = position.classify(&table);
+                if outcome != Outcome::Unknown {
+                    table[idx] = outcome;
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    let mut bits = vec![0; MAX_INDEX / 64];
+    for (idx, _) in table
+        .iter()
+        .enumerate()
+        .filter(|(_, o)| **o == Outcome::Win)
+    {
+        bits[idx / 64] |= 1 << (idx % 64);
+    }
+    bits
+}
+
+/// Generate the bitbase now instead of on first probe.
+pub fn init() {
+    BITBASE.get_or_init(generate);
+}
+
+/// Whether white (the side with the pawn) wins.
+pub fn probe(white_king: Square, pawn: Square, black_king: Square, white_to_move: bool) -> bool {
+    let (white_king, pawn, black_king) = if pawn.file() > 3 {
+        // Mirror onto the a-d files.
+        (
+            SQUARES[white_king as usize ^ 7],
+            SQUARES[pawn as usize ^ 7],
+            SQUARES[black_king as usize ^ 7],
+        )
+    } else {
+        (white_king, pawn, black_king)
+    };
+
+    let idx = index(white_to_move, white_king, black_king, pawn);
+    BITBASE.get_or_init(generate)[idx / 64] & 1 << (idx % 64) != 0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_probe() {
+        // King in front of its pawn on the sixth rank wins whoever moves.
+        assert!(probe(Square::E6, Square::E5, Square::E8, true));
+        assert!(probe(Square::E6, Square::E5, Square::E8, false));
+
+        // Black is stalemated.
+        assert!(!probe(Square::E6, Square::E7, Square::E8, false));
+        // Rook pawn with the defending king in the corner.
+        assert!(!probe(Square::E2, Square::A2, Square::A8, true));
+        assert!(!probe(Square::E2, Square::H2, Square::H8, true));
+
+        // Rule of the square.
+        assert!(probe(Square::A1, Square::D5, Square::H5, true));
+        assert!(!probe(Square::A1, Square::D5, Square::G5, true));
+
+        // Opposition.
+        assert!(probe(Square::E4, Square::E3, Square::E6, false));
+        assert!(!probe(Square::E4, Square::E3, Square::E6, true));
+    }
+
+    #[test]
+    fn test_win_count() {
+        let bitbase = BITBASE.get_or_init(generate);
+        let wins: u32 = bitbase.iter().map(|bits| bits.count_ones()).sum();
+        assert_eq!(wins, 111282);
+    }
+}
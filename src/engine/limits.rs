@@ -0,0 +1,90 @@
+use super::{moves::Move, score::UciScore, time::TimeControl};
+
+/// Everything a `go` command can bound a search by. Limits left at their
+/// default do not apply; with none set the search runs until told to stop.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct SearchLimits {
+    pub time: TimeControl,
+    pub depth: Option<u8>,
+    pub nodes: Option<u64>,
+    /// Stop once a mate in at most this many moves is found.
+    pub mate: Option<u32>,
+    /// Ignore every other limit until `stop`.
+    pub infinite: bool,
+    /// Root moves to consider; empty means all of them.
+    pub searchmoves: Vec<Move>,
+}
+
+impl SearchLimits {
+    pub fn depth(depth: u8) -> Self {
+        SearchLimits {
+            depth: Some(depth),
+            ..SearchLimits::default()
+        }
+    }
+
+    pub fn nodes(nodes: u64) -> Self {
+        SearchLimits {
+            nodes: Some(nodes),
+            ..SearchLimits::default()
+        }
+    }
+
+    pub fn allows_root_move(&self, mv: Move) -> bool {
+        self.searchmoves.is_empty() || self.searchmoves.contains(&mv)
+    }
+
+    /// Whether the node budget is spent. Checked inside the search, so that
+    /// node-limited searches stop at the same node on every run.
+    pub fn nodes_exhausted(&self, nodes: u64) -> bool {
+        !self.infinite && self.nodes.is_some_and(|limit| nodes >= limit)
+    }
+
+    /// Whether to stop after finishing an iteration at `depth` with `score`.
+    pub fn iteration_done(&self, depth: u8, score: i32) -> bool {
+        if self.infinite {
+            return false;
+        }
+        let deep_enough = self.depth.is_some_and(|limit| depth >= limit);
+        let mate_found = self.mate.is_some_and(|limit| {
+            UciScore(score)
+                .mate()
+                .is_some_and(|moves| moves > 0 && moves as u32 <= limit)
+        });
+        deep_enough || mate_found
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::engine::score::{mate_in, mated_in};
+
+    #[test]
+    fn test_limits() {
+        let limits = SearchLimits::depth(6);
+        assert!(!limits.iteration_done(5, 0));
+        assert!(limits.iteration_done(6, 0));
+        assert!(!limits.nodes_exhausted(u64::MAX));
+        assert!(limits.allows_root_move("e2e4".parse().unwrap()));
+
+        let limits = SearchLimits {
+            mate: Some(2),
+            searchmoves: vec!["d1h5".parse().unwrap()],
+            ..SearchLimits::nodes(1000)
+        };
+        assert!(!limits.iteration_done(20, mate_in(5)));
+        assert!(limits.iteration_done(3, mate_in(3)));
+        assert!(!limits.iteration_done(3, mated_in(2)));
+        assert!(limits.nodes_exhausted(1000));
+        assert!(!limits.allows_root_move("e2e4".parse().unwrap()));
+        assert!(limits.allows_root_move("d1h5".parse().unwrap()));
+
+        let infinite = SearchLimits {
+            infinite: true,
+            ..limits
+        };
+        assert!(!infinite.iteration_done(3, mate_in(1)));
+        assert!(!infinite.nodes_exhausted(1000));
+    }
+}
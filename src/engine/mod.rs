@@ -1,3 +1,15 @@
 pub mod bitboard;
 pub mod board;
+pub mod castling;
+pub mod endgame;
+pub mod history;
+pub mod kpk;
+pub mod limits;
+pub mod moves;
+pub mod ordering;
 pub mod pieces;
+pub mod render;
+pub mod score;
+pub mod time;
+pub mod tt;
+pub mod zobrist;
@@ -0,0 +1,365 @@
+use super::{
+    board::{ParseSquareError, Square},
+    pieces::PieceType,
+};
+use ::std::{error::Error, fmt::Display, str::FromStr};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ParseMoveError {
+    Square(ParseSquareError),
+    Promotion(String),
+    Syntax(String),
+}
+
+impl Display for ParseMoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Square(err) => write!(f, "{err}"),
+            Self::Promotion(s) => write!(f, "invalid promotion in `{s}`"),
+            Self::Syntax(s) => write!(f, "invalid move `{s}`"),
+        }
+    }
+}
+
+impl Error for ParseMoveError {}
+
+impl From<ParseSquareError> for ParseMoveError {
+    fn from(value: ParseSquareError) -> Self {
+        Self::Square(value)
+    }
+}
+
+/// Piece letter used by both SAN and long algebraic notation.
+fn letter(piece: PieceType) -> char {
+    match piece {
+        PieceType::NoPiece => ' ',
+        PieceType::WhitePawn | PieceType::BlackPawn => 'P',
+        PieceType::WhiteKnight | PieceType::BlackKnight => 'N',
+        PieceType::WhiteBishop | PieceType::BlackBishop => 'B',
+        PieceType::WhiteRook | PieceType::BlackRook => 'R',
+        PieceType::WhiteQueen | PieceType::BlackQueen => 'Q',
+        PieceType::WhiteKing | PieceType::BlackKing => 'K',
+    }
+}
+
+/// White piece for an upper-case letter, as notation is colour-agnostic.
+fn piece_of(letter: char) -> Option<PieceType> {
+    match letter {
+        'N' => Some(PieceType::WhiteKnight),
+        'B' => Some(PieceType::WhiteBishop),
+        'R' => Some(PieceType::WhiteRook),
+        'Q' => Some(PieceType::WhiteQueen),
+        'K' => Some(PieceType::WhiteKing),
+        _ => None,
+    }
+}
+
+/// A move in coordinate form, as exchanged over UCI.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Move {
+    pub from: Square,
+    pub to: Square,
+    pub promotion: Option<PieceType>,
+}
+
+impl Display for Move {
+    /// Long algebraic notation as used by UCI, e.g. `e2e4` or `e7e8q`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.from, self.to)?;
+        if let Some(promotion) = self.promotion {
+            write!(f, "{}", letter(promotion).to_ascii_lowercase())?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Move {
+    type Err = ParseMoveError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if !s.is_ascii() {
+            return Err(ParseMoveError::Syntax(s.to_string()));
+        }
+        let (from, to, promotion) = match s.len() {
+            4 | 5 => (&s[0..2], &s[2..4], s[4..].chars().next()),
+            _ => return Err(ParseMoveError::Syntax(s.to_string())),
+        };
+        let from: Square = from.parse()?;
+        let to: Square = to.parse()?;
+
+        let promotion = match promotion {
+            None => None,
+            Some(c) => {
+                let piece = piece_of(c.to_ascii_uppercase())
+                    .filter(|&piece| piece != PieceType::WhiteKing)
+                    .ok_or_else(|| ParseMoveError::Promotion(s.to_string()))?;
+                // The promoted piece takes the colour of the side reaching its last rank.
+                match to.rank() {
+                    7 => Some(piece),
+                    0 => Some(piece.to_black()),
+                    _ => return Err(ParseMoveError::Promotion(s.to_string())),
+                }
+            }
+        };
+
+        Ok(Move {
+            from,
+            to,
+            promotion,
+        })
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum Check {
+    #[default]
+    None,
+    Check,
+    Mate,
+}
+
+/// A move in Standard Algebraic Notation, as written rather than as resolved on a board.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum San {
+    Castle {
+        long: bool,
+        check: Check,
+    },
+    Normal {
+        /// White piece for the moving role; pawns are `PieceType::WhitePawn`.
+        piece: PieceType,
+        from_file: Option<u8>,
+        from_rank: Option<u8>,
+        capture: bool,
+        to: Square,
+        /// White piece for the promoted role.
+        promotion: Option<PieceType>,
+        check: Check,
+    },
+}
+
+impl Display for San {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let check = match self {
+            Self::Castle { long, check } => {
+                write!(f, "{}", if *long { "O-O-O" } else { "O-O" })?;
+                check
+            }
+            Self::Normal {
+                piece,
+                from_file,
+                from_rank,
+                capture,
+                to,
+                promotion,
+                check,
+            } => {
+                if *piece != PieceType::WhitePawn {
+                    write!(f, "{}", letter(*piece))?;
+                }
+                if let Some(file) = from_file {
+                    write!(f, "{}", (b'a' + file) as char)?;
+                }
+                if let Some(rank) = from_rank {
+                    write!(f, "{}", rank + 1)?;
+                }
+                if *capture {
+                    write!(f, "x")?;
+                }
+                write!(f, "{to}")?;
+                if let Some(promotion) = promotion {
+                    write!(f, "={}", letter(*promotion))?;
+                }
+                check
+            }
+        };
+        match check {
+            Check::None => Ok(()),
+            Check::Check => write!(f, "+"),
+            Check::Mate => write!(f, "#"),
+        }
+    }
+}
+
+impl FromStr for San {
+    type Err = ParseMoveError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let syntax = || ParseMoveError::Syntax(s.to_string());
+
+        let (body, check) = if let Some(body) = s.strip_suffix('#') {
+            (body, Check::Mate)
+        } else if let Some(body) = s.strip_suffix('+') {
+            (body, Check::Check)
+        } else {
+            (s, Check::None)
+        };
+
+        // Zeros are a common variant of the castling letters.
+        match body {
+            "O-O" | "0-0" => return Ok(San::Castle { long: false, check }),
+            "O-O-O" | "0-0-0" => return Ok(San::Castle { long: true, check }),
+            _ => {}
+        }
+        if !body.is_ascii() {
+            return Err(syntax());
+        }
+
+        let (body, promotion) = match body.split_once('=') {
+            Some((body, promotion)) => {
+                let piece = promotion
+                    .chars()
+                    .next()
+                    .filter(|_| promotion.len() == 1)
+                    .and_then(piece_of)
+                    .filter(|&piece| piece != PieceType::WhiteKing)
+                    .ok_or_else(|| ParseMoveError::Promotion(s.to_string()))?;
+                (body, Some(piece))
+            }
+            None => (body, None),
+        };
+
+        let (piece, body) = match body.chars().next().and_then(piece_of) {
+            Some(piece) => (piece, &body[1..]),
+            None => (PieceType::WhitePawn, body),
+        };
+
+        if body.len() < 2 {
+            return Err(syntax());
+        }
+        let (rest, to) = body.split_at(body.len() - 2);
+        let to: Square = to.parse()?;
+
+        let (rest, capture) = match rest.strip_suffix('x') {
+            Some(rest) => (rest, true),
+            None => (rest, false),
+        };
+
+        let (mut from_file, mut from_rank) = (None, None);
+        for c in rest.chars() {
+            match c {
+                'a'..='h' if from_file.is_none() && from_rank.is_none() => {
+                    from_file = Some(c as u8 - b'a')
+                }
+                '1'..='8' if from_rank.is_none() => from_rank = Some(c as u8 - b'1'),
+                _ => return Err(syntax()),
+            }
+        }
+
+        if piece == PieceType::WhitePawn {
+            // Pawn captures name their file and nothing else.
+            if capture != from_file.is_some() || from_rank.is_some() {
+                return Err(syntax());
+            }
+            if promotion.is_some() != (to.rank() == 0 || to.rank() == 7) {
+                return Err(ParseMoveError::Promotion(s.to_string()));
+            }
+        } else if promotion.is_some() {
+            return Err(ParseMoveError::Promotion(s.to_string()));
+        }
+
+        Ok(San::Normal {
+            piece,
+            from_file,
+            from_rank,
+            capture,
+            to,
+            promotion,
+            check,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_uci() {
+        let m: Move = "e2e4".parse().unwrap();
+        assert_eq!(
+            m,
+            Move {
+                from: Square::E2,
+                to: Square::E4,
+                promotion: None
+            }
+        );
+        assert_eq!(m.to_string(), "e2e4");
+
+        let m: Move = "e7e8q".parse().unwrap();
+        assert_eq!(m.promotion, Some(PieceType::WhiteQueen));
+        assert_eq!(m.to_string(), "e7e8q");
+
+        let m: Move = "b2a1n".parse().unwrap();
+        assert_eq!(m.promotion, Some(PieceType::BlackKnight));
+        assert_eq!(m.to_string(), "b2a1n");
+
+        assert!(matches!(
+            "e2e9".parse::<Move>(),
+            Err(ParseMoveError::Square(_))
+        ));
+        assert!(matches!(
+            "e7e8k".parse::<Move>(),
+            Err(ParseMoveError::Promotion(_))
+        ));
+        assert!(matches!(
+            "e6e7q".parse::<Move>(),
+            Err(ParseMoveError::Promotion(_))
+        ));
+        assert!(matches!(
+            "e2e4qq".parse::<Move>(),
+            Err(ParseMoveError::Syntax(_))
+        ));
+    }
+
+    #[test]
+    fn test_san() {
+        for san in [
+            "e4", "exd5", "Nf3", "Nbd7", "R1e2", "Qh4xe1", "Bxf7+", "O-O", "O-O-O#", "e8=Q",
+            "fxg1=N+", "Kxh8",
+        ] {
+            assert_eq!(san.parse::<San>().unwrap().to_string(), san);
+        }
+
+        assert_eq!(
+            "Nbxd7+".parse::<San>(),
+            Ok(San::Normal {
+                piece: PieceType::WhiteKnight,
+                from_file: Some(1),
+                from_rank: None,
+                capture: true,
+                to: Square::D7,
+                promotion: None,
+                check: Check::Check,
+            })
+        );
+        assert_eq!(
+            "0-0".parse::<San>(),
+            Ok(San::Castle {
+                long: false,
+                check: Check::None
+            })
+        );
+
+        assert!(matches!(
+            "e8".parse::<San>(),
+            Err(ParseMoveError::Promotion(_))
+        ));
+        assert!(matches!(
+            "Ne8=Q".parse::<San>(),
+            Err(ParseMoveError::Promotion(_))
+        ));
+        assert!(matches!(
+            "Nz3".parse::<San>(),
+            Err(ParseMoveError::Square(_))
+        ));
+        assert!(matches!(
+            "xd5".parse::<San>(),
+            Err(ParseMoveError::Syntax(_))
+        ));
+        assert!(matches!(
+            "N1bd7".parse::<San>(),
+            Err(ParseMoveError::Syntax(_))
+        ));
+        assert!(matches!("".parse::<San>(), Err(ParseMoveError::Syntax(_))));
+    }
+}
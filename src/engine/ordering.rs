@@ -0,0 +1,196 @@
+use super::{board::Square, moves::Move, pieces::PieceType};
+
+/// Deepest ply a search reaches: the size of the killer table, and the
+/// furthest mate distance `score::MATE_BOUND` allows for.
+pub const MAX_PLY: usize = 128;
+/// Bound of history scores, reached asymptotically by repeated bonuses.
+pub const MAX_HISTORY: i32 = 16_384;
+
+/// Score of a capture for "most valuable victim, least valuable attacker"
+/// ordering: any capture of a bigger piece goes first, ties broken by the
+/// cheaper attacker.
+pub fn mvv_lva(victim: PieceType, attacker: PieceType) -> i32 {
+    // Kings have no material value but are the last piece to capture with.
+    let attacker = match attacker.to_white() {
+        PieceType::NoPiece => 0,
+        PieceType::WhitePawn => 1,
+        PieceType::WhiteKnight => 2,
+        PieceType::WhiteBishop => 3,
+        PieceType::WhiteRook => 4,
+        PieceType::WhiteQueen => 5,
+        _ => 6,
+    };
+    victim.value() * 16 - attacker
+}
+
+/// Two quiet moves per ply that last caused a beta cutoff, most recent first.
+pub struct Killers {
+    moves: [[Option<Move>; 2]; MAX_PLY],
+}
+
+impl Default for Killers {
+    fn default() -> Self {
+        Killers {
+            moves: [[None; 2]; MAX_PLY],
+        }
+    }
+}
+
+impl Killers {
+    pub fn get(&self, ply: usize) -> [Option<Move>; 2] {
+        self.moves.get(ply).copied().unwrap_or_default()
+    }
+
+    pub fn store(&mut self, ply: usize, mv: Move) {
+        let Some(killers) = self.moves.get_mut(ply) else {
+            return;
+        };
+        if killers[0] != Some(mv) {
+            killers[1] = killers[0];
+            killers[0] = Some(mv);
+        }
+    }
+
+    pub fn is_killer(&self, ply: usize, mv: Move) -> bool {
+        self.get(ply).contains(&Some(mv))
+    }
+}
+
+/// Butterfly history: how often a quiet move from one square to another
+/// caused a cutoff, per side.
+pub struct ButterflyHistory {
+    scores: Box<[[[i32; 64]; 64]; 2]>,
+}
+
+impl Default for ButterflyHistory {
+    fn default() -> Self {
+        ButterflyHistory {
+            scores: Box::new([[[0; 64]; 64]; 2]),
+        }
+    }
+}
+
+/// Move `score` towards `MAX_HISTORY` (or its negation) by `bonus`, slowing
+/// down as it gets closer so scores never overflow and old results fade.
+fn gravity(score: &mut i32, bonus: i32) {
+    let bonus = bonus.clamp(-MAX_HISTORY, MAX_HISTORY);
+    *score += bonus - *score * bonus.abs() / MAX_HISTORY;
+}
+
+impl ButterflyHistory {
+    pub fn get(&self, white: bool, mv: Move) -> i32 {
+        self.scores[!white as usize][mv.from as usize][mv.to as usize]
+    }
+
+    /// Reward the move that caused a cutoff at `depth` and punish the quiet
+    /// moves searched before it.
+    pub fn update(&mut self, white: bool, depth: u8, best: Move, tried: &[Move]) {
+        let bonus = (depth as i32 * depth as i32).min(400) * 4;
+        let scores = &mut self.scores[!white as usize];
+        gravity(&mut scores[best.from as usize][best.to as usize], bonus);
+        for mv in tried.iter().filter(|&&mv| mv != best) {
+            gravity(&mut scores[mv.from as usize][mv.to as usize], -bonus);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self.scores = [[[0; 64]; 64]; 2];
+    }
+}
+
+/// The move that refuted each previous move, indexed by the piece that
+/// moved and its destination.
+pub struct CounterMoves {
+    moves: Box<[[Option<Move>; 64]; 12]>,
+}
+
+impl Default for CounterMoves {
+    fn default() -> Self {
+        CounterMoves {
+            moves: Box::new([[None; 64]; 12]),
+        }
+    }
+}
+
+impl CounterMoves {
+    pub fn get(&self, piece: PieceType, to: Square) -> Option<Move> {
+        self.moves
+            .get((piece as usize).wrapping_sub(1))
+            .and_then(|moves| moves[to as usize])
+    }
+
+    pub fn store(&mut self, piece: PieceType, to: Square, counter: Move) {
+        if let Some(moves) = self.moves.get_mut((piece as usize).wrapping_sub(1)) {
+            moves[to as usize] = Some(counter);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn mv(text: &str) -> Move {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn test_mvv_lva() {
+        let pxq = mvv_lva(PieceType::BlackQueen, PieceType::WhitePawn);
+        let qxq = mvv_lva(PieceType::BlackQueen, PieceType::WhiteQueen);
+        let pxr = mvv_lva(PieceType::BlackRook, PieceType::WhitePawn);
+        let kxn = mvv_lva(PieceType::BlackKnight, PieceType::WhiteKing);
+        let pxp = mvv_lva(PieceType::BlackPawn, PieceType::WhitePawn);
+        assert!(pxq > qxq && qxq > pxr && pxr > kxn && kxn > pxp);
+
+        let kxq = mvv_lva(PieceType::BlackQueen, PieceType::WhiteKing);
+        assert!(pxq > kxq && qxq > kxq && kxq > pxr);
+        let bxn = mvv_lva(PieceType::WhiteKnight, PieceType::BlackBishop);
+        assert!(bxn > mvv_lva(PieceType::WhiteKnight, PieceType::BlackKing));
+    }
+
+    #[test]
+    fn test_killers() {
+        let mut killers = Killers::default();
+        killers.store(3, mv("g1f3"));
+        killers.store(3, mv("g1f3"));
+        assert_eq!(killers.get(3), [Some(mv("g1f3")), None]);
+
+        killers.store(3, mv("b1c3"));
+        killers.store(3, mv("e2e4"));
+        assert_eq!(killers.get(3), [Some(mv("e2e4")), Some(mv("b1c3"))]);
+        assert!(!killers.is_killer(3, mv("g1f3")));
+        assert!(!killers.is_killer(4, mv("e2e4")));
+
+        killers.store(MAX_PLY, mv("e2e4"));
+        assert_eq!(killers.get(MAX_PLY), [None, None]);
+    }
+
+    #[test]
+    fn test_history() {
+        let mut history = ButterflyHistory::default();
+        let tried = [mv("a2a3"), mv("b1c3"), mv("g1f3")];
+        history.update(true, 4, mv("g1f3"), &tried);
+        assert!(history.get(true, mv("g1f3")) > 0);
+        assert!(history.get(true, mv("a2a3")) < 0);
+        assert_eq!(history.get(false, mv("g1f3")), 0);
+
+        for _ in 0..1000 {
+            history.update(true, 30, mv("g1f3"), &tried);
+        }
+        assert!(history.get(true, mv("g1f3")) <= MAX_HISTORY);
+        assert!(history.get(true, mv("a2a3")) >= -MAX_HISTORY);
+
+        history.clear();
+        assert_eq!(history.get(true, mv("g1f3")), 0);
+
+        let mut counters = CounterMoves::default();
+        counters.store(PieceType::WhitePawn, Square::E4, mv("e7e5"));
+        assert_eq!(
+            counters.get(PieceType::WhitePawn, Square::E4),
+            Some(mv("e7e5"))
+        );
+        assert_eq!(counters.get(PieceType::BlackPawn, Square::E4), None);
+        assert_eq!(counters.get(PieceType::NoPiece, Square::E4), None);
+    }
+}
@@ -45,6 +45,87 @@ impl PieceType {
         }
     }
 
+    /// FEN letter: upper case for White, lower case for Black.
+    pub fn as_char(&self) -> char {
+        match *self {
+            PieceType::NoPiece => ' ',
+            PieceType::WhitePawn => 'P',
+            PieceType::WhiteKnight => 'N',
+            PieceType::WhiteBishop => 'B',
+            PieceType::WhiteRook => 'R',
+            PieceType::WhiteQueen => 'Q',
+            PieceType::WhiteKing => 'K',
+            PieceType::BlackPawn => 'p',
+            PieceType::BlackKnight => 'n',
+            PieceType::BlackBishop => 'b',
+            PieceType::BlackRook => 'r',
+            PieceType::BlackQueen => 'q',
+            PieceType::BlackKing => 'k',
+        }
+    }
+
+    /// The same kind of piece in white. `NoPiece` stays as it is.
+    pub fn to_white(&self) -> PieceType {
+        match *self {
+            Self::BlackPawn => Self::WhitePawn,
+            Self::BlackKnight => Self::WhiteKnight,
+            Self::BlackBishop => Self::WhiteBishop,
+            Self::BlackRook => Self::WhiteRook,
+            Self::BlackQueen => Self::WhiteQueen,
+            Self::BlackKing => Self::WhiteKing,
+            other => other,
+        }
+    }
+
+    /// The same kind of piece in black. `NoPiece` stays as it is.
+    pub fn to_black(&self) -> PieceType {
+        match *self {
+            Self::WhitePawn => Self::BlackPawn,
+            Self::WhiteKnight => Self::BlackKnight,
+            Self::WhiteBishop => Self::BlackBishop,
+            Self::WhiteRook => Self::BlackRook,
+            Self::WhiteQueen => Self::BlackQueen,
+            Self::WhiteKing => Self::BlackKing,
+            other => other,
+        }
+    }
+
+    pub fn is_white(&self) -> bool {
+        matches!(
+            *self,
+            Self::WhitePawn
+                | Self::WhiteKnight
+                | Self::WhiteBishop
+                | Self::WhiteRook
+                | Self::WhiteQueen
+                | Self::WhiteKing
+        )
+    }
+
+    pub fn is_black(&self) -> bool {
+        matches!(
+            *self,
+            Self::BlackPawn
+                | Self::BlackKnight
+                | Self::BlackBishop
+                | Self::BlackRook
+                | Self::BlackQueen
+                | Self::BlackKing
+        )
+    }
+
+    /// Material value in centipawns, kings excluded.
+    pub fn value(&self) -> i32 {
+        match *self {
+            Self::NoPiece | Self::WhiteKing | Self::BlackKing => 0,
+            Self::WhitePawn | Self::BlackPawn => 100,
+            Self::WhiteKnight | Self::BlackKnight => 320,
+            Self::WhiteBishop | Self::BlackBishop => 330,
+            Self::WhiteRook | Self::BlackRook => 500,
+            Self::WhiteQueen | Self::BlackQueen => 900,
+        }
+    }
+
     pub fn moves(&self, piece: BitBoard) -> BitBoard {
         match *self {
             Self::NoPiece => bitboard::EMPTY,
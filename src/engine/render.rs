@@ -0,0 +1,250 @@
+use super::{
+    bitboard::{BitBoard, EMPTY},
+    board::{Board, SQUARES, Square},
+    moves::Move,
+    score::UciScore,
+};
+use ::std::fmt::Write;
+
+const RESET: &str = "\x1b[0m";
+const LIGHT: &str = "\x1b[48;5;180m";
+const DARK: &str = "\x1b[48;5;137m";
+const LAST_MOVE: &str = "\x1b[48;5;143m";
+const CHECK: &str = "\x1b[48;5;167m";
+const OVERLAY: &str = "\x1b[48;5;74m";
+
+/// How `Board::render` draws a board. The default draws it like `Display`:
+/// from White's side, with Unicode pieces and a grid.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct RenderOptions {
+    /// Draw from Black's side, with rank 1 at the top and the h-file on the left.
+    pub flipped: bool,
+    /// Piece letters and an ASCII grid, for terminals without Unicode.
+    pub ascii: bool,
+    /// Colour squares with ANSI escapes instead of drawing a grid.
+    pub colour: bool,
+    pub last_move: Option<Move>,
+    /// Square of the king in check.
+    pub check: Option<Square>,
+    /// Squares to mark on top of the pieces, such as an attack mask.
+    pub overlay: BitBoard,
+    pub fen: Option<String>,
+    /// Evaluation in centipawns from White's point of view.
+    pub eval: Option<i32>,
+}
+
+/// Box-drawing characters of a grid: left, middle and right pieces of the
+/// top, middle and bottom lines, then the vertical bar and the horizontal line.
+struct Grid {
+    top: [&'static str; 3],
+    middle: [&'static str; 3],
+    bottom: [&'static str; 3],
+    bar: &'static str,
+    line: &'static str,
+}
+
+const UNICODE: Grid = Grid {
+    top: ["┌", "┬", "┐"],
+    middle: ["├", "┼", "┤"],
+    bottom: ["└", "┴", "┘"],
+    bar: "│",
+    line: "──",
+};
+
+const ASCII: Grid = Grid {
+    top: ["+", "+", "+"],
+    middle: ["+", "+", "+"],
+    bottom: ["+", "+", "+"],
+    bar: "|",
+    line: "--",
+};
+
+impl Grid {
+    fn line(&self, [left, middle, right]: [&str; 3]) -> String {
+        format!("  {left}{}{right}\n", [self.line; 8].join(middle))
+    }
+}
+
+/// Evaluation as a pawn count, or `#N` for a forced mate in N moves.
+fn format_eval(eval: i32) -> String {
+    match UciScore(eval).mate() {
+        Some(moves) => format!("#{moves}"),
+        None => format!("{:+.2}", eval as f64 / 100.0),
+    }
+}
+
+impl<T> Board<T> {
+    /// Draw the board as text according to `options`.
+    pub fn render(&self, options: &RenderOptions) -> String {
+        let grid = if options.ascii { &ASCII } else { &UNICODE };
+        let highlights = options
+            .last_move
+            .map_or(EMPTY, |mv| BitBoard::from(&[mv.from, mv.to][..]));
+
+        let ranks: Vec<usize> = if options.flipped {
+            (0..8).collect()
+        } else {
+            (0..8).rev().collect()
+        };
+        let files: Vec<usize> = if options.flipped {
+            (0..8).rev().collect()
+        } else {
+            (0..8).collect()
+        };
+
+        let mut out = String::new();
+        if !options.colour {
+            out += &grid.line(grid.top);
+        }
+        for (row, &rank) in ranks.iter().enumerate() {
+            if row > 0 && !options.colour {
+                out += &grid.line(grid.middle);
+            }
+            let _ = write!(out, "{} ", rank + 1);
+            if !options.colour {
+                out += grid.bar;
+            }
+            for &file in &files {
+                let square = SQUARES[rank * 8 + file];
+                let piece = self.get_piece(square);
+                let glyph = if options.ascii {
+                    piece.as_char().to_string()
+                } else {
+                    piece.to_string()
+                };
+                let check = options.check == Some(square);
+                let highlighted = highlights.check(square);
+                let overlaid = options.overlay.check(square);
+
+                if options.colour {
+                    let background = if check {
+                        CHECK
+                    } else if overlaid {
+                        OVERLAY
+                    } else if highlighted {
+                        LAST_MOVE
+                    } else if (rank + file) % 2 == 0 {
+                        DARK
+                    } else {
+                        LIGHT
+                    };
+                    let _ = write!(out, "{background} {glyph} {RESET}");
+                } else {
+                    // Without colours, mark squares next to the piece.
+                    let mark = if check {
+                        '+'
+                    } else if overlaid {
+                        'x'
+                    } else if highlighted {
+                        '*'
+                    } else {
+                        ' '
+                    };
+                    let _ = write!(out, "{glyph}{mark}{}", grid.bar);
+                }
+            }
+            out += "\n";
+        }
+        if !options.colour {
+            out += &grid.line(grid.bottom);
+        }
+
+        out += "  ";
+        for &file in &files {
+            let _ = write!(out, " {} ", (b'a' + file as u8) as char);
+        }
+        out = out.trim_end().to_string() + "\n";
+
+        if let Some(fen) = &options.fen {
+            let _ = writeln!(out, "FEN: {fen}");
+        }
+        if let Some(eval) = options.eval {
+            let _ = writeln!(out, "Eval: {}", format_eval(eval));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::engine::{board::White, pieces::PieceType, score::mate_in};
+
+    fn board() -> Board<White> {
+        Board::<White>::empty()
+            .set_piece(PieceType::WhiteKing, Square::E1)
+            .set_piece(PieceType::WhiteRook, Square::E7)
+            .set_piece(PieceType::BlackKing, Square::H8)
+    }
+
+    #[test]
+    fn test_ascii() {
+        let options = RenderOptions {
+            ascii: true,
+            last_move: Some("e2e7".parse().unwrap()),
+            ..RenderOptions::default()
+        };
+        let text = board().render(&options);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 18);
+        assert_eq!(lines[0], "  +--+--+--+--+--+--+--+--+");
+        assert_eq!(lines[1], "8 |  |  |  |  |  |  |  |k |");
+        assert_eq!(lines[3], "7 |  |  |  |  |R*|  |  |  |");
+        assert_eq!(lines[13], "2 |  |  |  |  | *|  |  |  |");
+        assert_eq!(lines[15], "1 |  |  |  |  |K |  |  |  |");
+        assert_eq!(lines[17], "   a  b  c  d  e  f  g  h");
+        assert!(text.is_ascii());
+    }
+
+    #[test]
+    fn test_flipped() {
+        let options = RenderOptions {
+            flipped: true,
+            check: Some(Square::H8),
+            eval: Some(mate_in(1)),
+            fen: Some("7k/4R3/8/8/8/8/8/4K3 b - - 0 1".to_string()),
+            ..RenderOptions::default()
+        };
+        let text = board().render(&options);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "  ┌──┬──┬──┬──┬──┬──┬──┬──┐");
+        assert_eq!(lines[1], "1 │  │  │  │♔ │  │  │  │  │");
+        assert_eq!(lines[15], "8 │♚+│  │  │  │  │  │  │  │");
+        assert_eq!(lines[17], "   h  g  f  e  d  c  b  a");
+        assert_eq!(lines[18], "FEN: 7k/4R3/8/8/8/8/8/4K3 b - - 0 1");
+        assert_eq!(lines[19], "Eval: #1");
+
+        assert_eq!(format_eval(-35), "-0.35");
+        assert_eq!(format_eval(120), "+1.20");
+    }
+
+    #[test]
+    fn test_overlay() {
+        let options = RenderOptions {
+            ascii: true,
+            overlay: BitBoard::from(&[Square::E8, Square::H8, Square::E2][..]),
+            last_move: Some("e2e7".parse().unwrap()),
+            ..RenderOptions::default()
+        };
+        let lines: Vec<String> = board().render(&options).lines().map(String::from).collect();
+        assert_eq!(lines[1], "8 |  |  |  |  | x|  |  |kx|");
+        assert_eq!(lines[3], "7 |  |  |  |  |R*|  |  |  |");
+        assert_eq!(lines[13], "2 |  |  |  |  | x|  |  |  |");
+    }
+
+    #[test]
+    fn test_colour() {
+        let options = RenderOptions {
+            colour: true,
+            check: Some(Square::H8),
+            ..RenderOptions::default()
+        };
+        let text = board().render(&options);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 9);
+        // a8 is a light square, h8 holds the king in check.
+        assert!(lines[0].starts_with(&format!("8 {LIGHT}   {RESET}{DARK}")));
+        assert!(lines[0].ends_with(&format!("{CHECK} ♚ {RESET}")));
+        assert!(lines[7].starts_with(&format!("1 {DARK}   {RESET}")));
+    }
+}
@@ -0,0 +1,115 @@
+use super::{endgame::KNOWN_WIN, ordering::MAX_PLY};
+use ::std::fmt::Display;
+
+/// Score of being checkmated at the root. A mate found `ply` half-moves
+/// from the root scores `MATE - ply`, so shorter mates score higher.
+pub const MATE: i32 = 32_000;
+/// Scores beyond this are mates, found at most `MAX_PLY` half-moves from
+/// the root. It stays above the endgame's known-win scores.
+pub const MATE_BOUND: i32 = MATE - MAX_PLY as i32;
+
+const _: () = assert!(MATE_BOUND > KNOWN_WIN * 2);
+const _: () = assert!(MATE <= i16::MAX as i32);
+
+/// Score of the side to move when it mates `ply` half-moves from the root.
+pub fn mate_in(ply: u32) -> i32 {
+    MATE - ply as i32
+}
+
+/// Score of the side to move when it is mated `ply` half-moves from the root.
+pub fn mated_in(ply: u32) -> i32 {
+    -MATE + ply as i32
+}
+
+pub fn is_mate(score: i32) -> bool {
+    score.abs() >= MATE_BOUND
+}
+
+/// Convert a score relative to the root into one relative to the node at
+/// `ply`, for the transposition table: the same position reached at another
+/// ply must report the mate distance from itself, not from the first root.
+pub fn to_tt(score: i32, ply: u32) -> i16 {
+    let score = if score >= MATE_BOUND {
+        score + ply as i32
+    } else if score <= -MATE_BOUND {
+        score - ply as i32
+    } else {
+        score
+    };
+    score as i16
+}
+
+/// Inverse of `to_tt` for a node at `ply`.
+pub fn from_tt(score: i16, ply: u32) -> i32 {
+    let score = score as i32;
+    if score >= MATE_BOUND {
+        score - ply as i32
+    } else if score <= -MATE_BOUND {
+        score + ply as i32
+    } else {
+        score
+    }
+}
+
+/// Score in UCI `info` form: `cp <centipawns>`, or `mate <moves>`, negative
+/// when the side to move is getting mated.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct UciScore(pub i32);
+
+impl UciScore {
+    /// Full moves to mate, if the score is a mate.
+    pub fn mate(&self) -> Option<i32> {
+        let score = self.0;
+        if score >= MATE_BOUND {
+            Some((MATE - score + 1) / 2)
+        } else if score <= -MATE_BOUND {
+            Some(-(MATE + score) / 2)
+        } else {
+            None
+        }
+    }
+}
+
+impl Display for UciScore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.mate() {
+            Some(moves) => write!(f, "mate {moves}"),
+            None => write!(f, "cp {}", self.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_uci() {
+        assert_eq!(UciScore(35).to_string(), "cp 35");
+        assert_eq!(UciScore(-KNOWN_WIN).to_string(), "cp -10000");
+        // Mate on the next move is one ply away.
+        assert_eq!(UciScore(mate_in(1)).to_string(), "mate 1");
+        assert_eq!(UciScore(mate_in(3)).to_string(), "mate 2");
+        assert_eq!(UciScore(mate_in(4)).to_string(), "mate 2");
+        // Checkmated now, or after our move and the reply.
+        assert_eq!(UciScore(mated_in(0)).to_string(), "mate 0");
+        assert_eq!(UciScore(mated_in(2)).to_string(), "mate -1");
+        assert!(is_mate(mated_in(40)) && !is_mate(KNOWN_WIN));
+    }
+
+    #[test]
+    fn test_tt() {
+        // Mate found 7 plies from the root, at a node 3 plies deep: stored as
+        // mate in 4 from that node.
+        let stored = to_tt(mate_in(7), 3);
+        assert_eq!(stored as i32, mate_in(4));
+        // Reached again 5 plies from a later root: mate in 9 from there.
+        assert_eq!(from_tt(stored, 5), mate_in(9));
+
+        let stored = to_tt(mated_in(6), 2);
+        assert_eq!(from_tt(stored, 2), mated_in(6));
+        assert_eq!(from_tt(stored, 0), mated_in(4));
+
+        assert_eq!(from_tt(to_tt(-250, 9), 1), -250);
+    }
+}
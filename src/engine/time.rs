@@ -0,0 +1,251 @@
+use ::std::time::{Duration, Instant};
+
+/// Moves assumed left in the game when the GUI does not send `movestogo`.
+const DEFAULT_MOVES_TO_GO: u32 = 30;
+/// How far the soft limit can be pushed past its base value.
+const MAX_EXTENSION: f64 = 3.0;
+/// Share of the root nodes spent on the best move above which it is
+/// considered the only sensible choice.
+const DOMINANT_EFFORT: f64 = 0.9;
+
+/// Time source of the time manager, so tests can run without waiting.
+pub trait TimeSource {
+    /// Time elapsed since an arbitrary but fixed origin.
+    fn now(&self) -> Duration;
+}
+
+pub struct SystemTimeSource(Instant);
+
+impl Default for SystemTimeSource {
+    fn default() -> Self {
+        SystemTimeSource(Instant::now())
+    }
+}
+
+impl TimeSource for SystemTimeSource {
+    fn now(&self) -> Duration {
+        self.0.elapsed()
+    }
+}
+
+/// Clock parameters of a `go` command.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct TimeControl {
+    pub wtime: Option<Duration>,
+    pub btime: Option<Duration>,
+    pub winc: Duration,
+    pub binc: Duration,
+    pub movestogo: Option<u32>,
+    pub movetime: Option<Duration>,
+}
+
+/// Decides how long to think on a move: a soft limit checked between
+/// iterations and scaled by how the search is going, and a hard limit at
+/// which the search is aborted.
+pub struct TimeManager<S: TimeSource> {
+    source: S,
+    start: Duration,
+    /// `None` when thinking is unbounded.
+    limits: Option<(Duration, Duration)>,
+    /// Whether the soft limit may be adjusted, which `movetime` forbids.
+    adaptive: bool,
+    instability: f64,
+    fail_low: bool,
+    dominant: bool,
+}
+
+impl<S: TimeSource> TimeManager<S> {
+    /// Start timing a move. `move_overhead` is kept in reserve for the
+    /// communication lag between engine and GUI on every move.
+    pub fn new(
+        source: S,
+        control: &TimeControl,
+        white_to_move: bool,
+        move_overhead: Duration,
+    ) -> Self {
+        let start = source.now();
+        let (time, increment) = if white_to_move {
+            (control.wtime, control.winc)
+        } else {
+            (control.btime, control.binc)
+        };
+
+        let (limits, adaptive) = if let Some(movetime) = control.movetime {
+            let limit = movetime.saturating_sub(move_overhead);
+            (Some((limit, limit)), false)
+        } else if let Some(time) = time {
+            let moves_to_go = control
+                .movestogo
+                .unwrap_or(DEFAULT_MOVES_TO_GO)
+                .clamp(1, DEFAULT_MOVES_TO_GO);
+            // Keep the overhead for every move still to be played before the next control.
+            let available = time.saturating_sub(move_overhead * moves_to_go.min(10));
+            let hard = available.mul_f64(if moves_to_go == 1 { 0.9 } else { 0.5 });
+            let soft = (available / moves_to_go + increment * 3 / 4).min(hard);
+            (Some((soft, hard)), true)
+        } else {
+            (None, false)
+        };
+
+        TimeManager {
+            source,
+            start,
+            limits,
+            adaptive,
+            instability: 0.0,
+            fail_low: false,
+            dominant: false,
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.source.now().saturating_sub(self.start)
+    }
+
+    /// Report a finished iteration: whether its best move differs from the
+    /// previous one, whether the score fell below the previous iteration's,
+    /// and the share of root nodes spent on the best move.
+    pub fn update(&mut self, best_move_changed: bool, fail_low: bool, best_move_effort: f64) {
+        self.instability = self.instability / 2.0 + if best_move_changed { 1.0 } else { 0.0 };
+        self.fail_low = fail_low;
+        self.dominant = best_move_effort >= DOMINANT_EFFORT;
+    }
+
+    /// Current soft limit, after extensions and reductions.
+    pub fn soft_limit(&self) -> Option<Duration> {
+        let (soft, hard) = self.limits?;
+        if !self.adaptive {
+            return Some(soft);
+        }
+
+        let mut scale = 1.0 + self.instability / 2.0;
+        if self.fail_low {
+            scale *= 1.5;
+        }
+        if self.dominant {
+            scale /= 2.0;
+        }
+        Some(soft.mul_f64(scale.min(MAX_EXTENSION)).min(hard))
+    }
+
+    pub fn hard_limit(&self) -> Option<Duration> {
+        self.limits.map(|(_, hard)| hard)
+    }
+
+    /// Whether to start another iteration.
+    pub fn should_stop(&self) -> bool {
+        self.soft_limit().is_some_and(|soft| self.elapsed() >= soft)
+    }
+
+    /// Whether to abort the iteration in progress.
+    pub fn must_stop(&self) -> bool {
+        self.hard_limit().is_some_and(|hard| self.elapsed() >= hard)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::std::{cell::Cell, rc::Rc};
+
+    #[derive(Default, Clone)]
+    struct FakeTimeSource(Rc<Cell<Duration>>);
+
+    impl FakeTimeSource {
+        fn advance(&self, by: Duration) {
+            self.0.set(self.0.get() + by);
+        }
+    }
+
+    impl TimeSource for FakeTimeSource {
+        fn now(&self) -> Duration {
+            self.0.get()
+        }
+    }
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn test_movetime() {
+        let source = FakeTimeSource::default();
+        let control = TimeControl {
+            movetime: Some(ms(1000)),
+            ..TimeControl::default()
+        };
+        let mut manager = TimeManager::new(source.clone(), &control, true, ms(50));
+        assert_eq!(manager.soft_limit(), Some(ms(950)));
+        assert_eq!(manager.hard_limit(), Some(ms(950)));
+
+        // A fixed move time is never stretched.
+        manager.update(true, true, 0.1);
+        assert_eq!(manager.soft_limit(), Some(ms(950)));
+
+        source.advance(ms(949));
+        assert!(!manager.should_stop());
+        source.advance(ms(1));
+        assert!(manager.should_stop() && manager.must_stop());
+    }
+
+    #[test]
+    fn test_clock() {
+        let source = FakeTimeSource::default();
+        let control = TimeControl {
+            wtime: Some(ms(60_000)),
+            btime: Some(ms(1_000)),
+            winc: ms(1_000),
+            ..TimeControl::default()
+        };
+        let manager = TimeManager::new(source.clone(), &control, true, ms(0));
+        assert_eq!(manager.soft_limit(), Some(ms(2_750)));
+        assert_eq!(manager.hard_limit(), Some(ms(30_000)));
+
+        // Black's clock, and the overhead kept for ten moves.
+        let manager = TimeManager::new(source.clone(), &control, false, ms(10));
+        assert_eq!(manager.soft_limit(), Some(ms(30)));
+
+        let control = TimeControl {
+            wtime: Some(ms(10_000)),
+            movestogo: Some(1),
+            ..TimeControl::default()
+        };
+        let manager = TimeManager::new(source.clone(), &control, true, ms(100));
+        assert_eq!(manager.hard_limit(), Some(ms(8_910)));
+        assert_eq!(manager.soft_limit(), Some(ms(8_910)));
+
+        let infinite = TimeManager::new(source.clone(), &TimeControl::default(), true, ms(0));
+        source.advance(Duration::from_secs(3600));
+        assert!(!infinite.should_stop() && !infinite.must_stop());
+    }
+
+    #[test]
+    fn test_adjustments() {
+        let source = FakeTimeSource::default();
+        let control = TimeControl {
+            wtime: Some(ms(30_000)),
+            ..TimeControl::default()
+        };
+        let mut manager = TimeManager::new(source.clone(), &control, true, ms(0));
+        let base = manager.soft_limit().unwrap();
+        assert_eq!(base, ms(1_000));
+
+        manager.update(false, true, 0.5);
+        assert_eq!(manager.soft_limit(), Some(ms(1_500)));
+
+        manager.update(true, false, 0.5);
+        assert_eq!(manager.soft_limit(), Some(ms(1_500)));
+        manager.update(true, false, 0.5);
+        assert_eq!(manager.soft_limit(), Some(ms(1_750)));
+
+        // Settling down on one move that takes all the effort.
+        for _ in 0..20 {
+            manager.update(false, false, 0.95);
+        }
+        assert!(manager.soft_limit().unwrap() < base);
+
+        source.advance(ms(600));
+        assert!(manager.should_stop());
+        assert!(!manager.must_stop());
+    }
+}
@@ -0,0 +1,281 @@
+use super::{
+    board::{SQUARES, Square},
+    moves::Move,
+    pieces::PieceType,
+};
+use ::std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Bound {
+    Exact,
+    /// The score is at least this (fail high).
+    Lower,
+    /// The score is at most this (fail low).
+    Upper,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Entry {
+    pub mv: Option<Move>,
+    pub score: i16,
+    pub depth: u8,
+    pub bound: Bound,
+}
+
+const PROMOTIONS: [PieceType; 4] = [
+    PieceType::WhiteKnight,
+    PieceType::WhiteBishop,
+    PieceType::WhiteRook,
+    PieceType::WhiteQueen,
+];
+
+fn encode_move(mv: Option<Move>) -> u64 {
+    let Some(mv) = mv else {
+        return 0;
+    };
+    let promotion = mv.promotion.map_or(0, |piece| {
+        PROMOTIONS
+            .iter()
+            .position(|&p| p == piece.to_white())
+            .map_or(0, |i| i + 1)
+    });
+    // From and to are never equal, so a real move never encodes to zero.
+    mv.from as u64 | (mv.to as u64) << 6 | (promotion as u64) << 12
+}
+
+fn decode_move(bits: u64) -> Option<Move> {
+    if bits == 0 {
+        return None;
+    }
+    let to: Square = SQUARES[(bits >> 6 & 0x3F) as usize];
+    let promotion = match bits >> 12 & 0x7 {
+        0 => None,
+        i => {
+            let piece = PROMOTIONS[i as usize - 1];
+            Some(if to.rank() == 0 {
+                piece.to_black()
+            } else {
+                piece
+            })
+        }
+    };
+    Some(Move {
+        from: SQUARES[(bits & 0x3F) as usize],
+        to,
+        promotion,
+    })
+}
+
+/// Data word layout: move (15 bits), score (16), depth (8), bound (2),
+/// generation (8), and a top bit so that no stored entry is zero.
+fn pack(entry: &Entry, generation: u8) -> u64 {
+    let bound = match entry.bound {
+        Bound::Exact => 0,
+        Bound::Lower => 1,
+        Bound::Upper => 2,
+    };
+    encode_move(entry.mv)
+        | (entry.score as u16 as u64) << 16
+        | (entry.depth as u64) << 32
+        | bound << 40
+        | (generation as u64) << 48
+        | 1 << 63
+}
+
+fn unpack(data: u64) -> (Entry, u8) {
+    let bound = match data >> 40 & 0x3 {
+        0 => Bound::Exact,
+        1 => Bound::Lower,
+        _ => Bound::Upper,
+    };
+    (
+        Entry {
+            mv: decode_move(data & 0xFFFF),
+            score: (data >> 16) as u16 as i16,
+            depth: (data >> 32) as u8,
+            bound,
+        },
+        (data >> 48) as u8,
+    )
+}
+
+struct Slot {
+    /// Zobrist key xor data, so a torn write by another thread fails the key check.
+    check: AtomicU64,
+    data: AtomicU64,
+}
+
+/// Transposition table shared between search threads without locks.
+///
+/// Each slot stores the key xor-ed with its data ("lockless hashing" on
+/// https://www.chessprogramming.org/Shared_Hash_Table#Lockless): a slot
+/// half-written by one thread while another reads it no longer matches
+/// its key and is treated as a miss.
+pub struct TranspositionTable {
+    slots: Vec<Slot>,
+    generation: AtomicU8,
+}
+
+impl TranspositionTable {
+    /// Table of about `megabytes` MiB.
+    pub fn new(megabytes: usize) -> Self {
+        let count = (megabytes * 1024 * 1024 / size_of::<Slot>()).max(1);
+        TranspositionTable {
+            slots: (0..count)
+                .map(|_| Slot {
+                    check: AtomicU64::new(0),
+                    data: AtomicU64::new(0),
+                })
+                .collect(),
+            generation: AtomicU8::new(0),
+        }
+    }
+
+    fn slot(&self, key: u64) -> &Slot {
+        // Multiply-shift maps the key onto the table without a modulo.
+        &self.slots[((key as u128 * self.slots.len() as u128) >> 64) as usize]
+    }
+
+    pub fn probe(&self, key: u64) -> Option<Entry> {
+        let slot = self.slot(key);
+        let data = slot.data.load(Ordering::Relaxed);
+        let check = slot.check.load(Ordering::Relaxed);
+        (data != 0 && check ^ data == key).then(|| unpack(data).0)
+    }
+
+    /// Store `entry`, replacing entries of earlier searches, of other
+    /// positions, or of the same position searched less deeply.
+    pub fn store(&self, key: u64, entry: Entry) {
+        let slot = self.slot(key);
+        let generation = self.generation.load(Ordering::Relaxed);
+        let old = slot.data.load(Ordering::Relaxed);
+        let same = slot.check.load(Ordering::Relaxed) ^ old == key;
+
+        if old != 0 {
+            let (old_entry, old_generation) = unpack(old);
+            if old_generation == generation
+                && same
+                && entry.bound != Bound::Exact
+                && entry.depth < old_entry.depth
+            {
+                return;
+            }
+        }
+
+        // Keep the old move when the new entry has none for the same position.
+        let entry = match (entry.mv, same && old != 0) {
+            (None, true) => Entry {
+                mv: unpack(old).0.mv,
+                ..entry
+            },
+            _ => entry,
+        };
+        let data = pack(&entry, generation);
+        slot.check.store(key ^ data, Ordering::Relaxed);
+        slot.data.store(data, Ordering::Relaxed);
+    }
+
+    /// Start a new search, making the previous search's entries the first
+    /// to be replaced.
+    pub fn new_search(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn clear(&self) {
+        for slot in &self.slots {
+            slot.check.store(0, Ordering::Relaxed);
+            slot.data.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Permille of the first thousand slots used by the current search, as
+    /// reported by UCI `hashfull`.
+    pub fn hashfull(&self) -> usize {
+        let generation = self.generation.load(Ordering::Relaxed);
+        let sample = &self.slots[..self.slots.len().min(1000)];
+        let used = sample
+            .iter()
+            .filter(|slot| {
+                let data = slot.data.load(Ordering::Relaxed);
+                data != 0 && unpack(data).1 == generation
+            })
+            .count();
+        used * 1000 / sample.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::std::thread;
+
+    fn entry(depth: u8, score: i16) -> Entry {
+        Entry {
+            mv: Some("e7e8n".parse().unwrap()),
+            score,
+            depth,
+            bound: Bound::Lower,
+        }
+    }
+
+    #[test]
+    fn test_store_probe() {
+        let tt = TranspositionTable::new(1);
+        assert_eq!(tt.probe(42), None);
+
+        tt.store(42, entry(5, -300));
+        assert_eq!(tt.probe(42), Some(entry(5, -300)));
+        assert_eq!(tt.probe(43), None);
+
+        // A shallower search of the same position does not overwrite...
+        tt.store(42, entry(3, 10));
+        assert_eq!(tt.probe(42), Some(entry(5, -300)));
+        // ...unless the old entry is from a previous search.
+        tt.new_search();
+        tt.store(42, entry(3, 10));
+        assert_eq!(tt.probe(42), Some(entry(3, 10)));
+
+        tt.store(
+            42,
+            Entry {
+                mv: None,
+                score: 7,
+                depth: 9,
+                bound: Bound::Exact,
+            },
+        );
+        let kept = tt.probe(42).unwrap();
+        assert_eq!(kept.mv, entry(0, 0).mv);
+        assert_eq!(kept.bound, Bound::Exact);
+
+        let black: Move = "b2a1q".parse().unwrap();
+        assert_eq!(decode_move(encode_move(Some(black))), Some(black));
+
+        tt.clear();
+        assert_eq!(tt.probe(42), None);
+        assert_eq!(tt.hashfull(), 0);
+    }
+
+    #[test]
+    fn test_threads() {
+        let tt = TranspositionTable::new(1);
+        // Every thread writes scores derived from the key, so any entry that
+        // passes the key check must carry its own key's score.
+        thread::scope(|scope| {
+            for t in 0..4u64 {
+                let tt = &tt;
+                scope.spawn(move || {
+                    for i in 0..50_000u64 {
+                        let key = (i * 4 + t).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+                        tt.store(key, entry((key % 64) as u8, (key >> 48) as i16));
+                        let probe = key.wrapping_sub(0x9E37_79B9_7F4A_7C15);
+                        if let Some(found) = tt.probe(probe) {
+                            assert_eq!(found.score, (probe >> 48) as i16);
+                        }
+                    }
+                });
+            }
+        });
+        assert!(tt.hashfull() > 0);
+    }
+}
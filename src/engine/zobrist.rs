@@ -0,0 +1,108 @@
+use super::{
+    board::{Black, Board, White},
+    pieces::PieceType,
+};
+
+const PIECES: [PieceType; 12] = [
+    PieceType::WhitePawn,
+    PieceType::WhiteKnight,
+    PieceType::WhiteBishop,
+    PieceType::WhiteRook,
+    PieceType::WhiteQueen,
+    PieceType::WhiteKing,
+    PieceType::BlackPawn,
+    PieceType::BlackKnight,
+    PieceType::BlackBishop,
+    PieceType::BlackRook,
+    PieceType::BlackQueen,
+    PieceType::BlackKing,
+];
+
+/// xorshift64* on https://www.chessprogramming.org/Pseudo-Random_Number_Generator
+const fn next(state: u64) -> u64 {
+    let mut x = state;
+    x ^= x >> 12;
+    x ^= x << 25;
+    x ^= x >> 27;
+    x
+}
+
+const fn keys() -> ([[u64; 64]; 12], u64) {
+    let mut state = 0x0A3B_7E55_1D2C_9F41;
+    let mut keys = [[0; 64]; 12];
+    let mut piece = 0;
+    while piece < 12 {
+        let mut square = 0;
+        while square < 64 {
+            state = next(state);
+            keys[piece][square] = state.wrapping_mul(0x2545_F491_4F6C_DD1D);
+            square += 1;
+        }
+        piece += 1;
+    }
+    state = next(state);
+    (keys, state.wrapping_mul(0x2545_F491_4F6C_DD1D))
+}
+
+const KEYS: ([[u64; 64]; 12], u64) = keys();
+/// Piece-square keys, indexed by `PieceType as usize - 1`.
+pub const PIECE_KEYS: [[u64; 64]; 12] = KEYS.0;
+/// Toggled when black is to move.
+pub const BLACK_TO_MOVE: u64 = KEYS.1;
+
+impl<T> Board<T> {
+    fn piece_key(&self) -> u64 {
+        PIECES
+            .iter()
+            .flat_map(|&piece| {
+                self.pieces(piece)
+                    .squares()
+                    .map(move |square| PIECE_KEYS[piece as usize - 1][square as usize])
+            })
+            .fold(0, |key, k| key ^ k)
+    }
+}
+
+impl Board<White> {
+    /// Zobrist key of the position.
+    pub fn key(&self) -> u64 {
+        self.piece_key()
+    }
+}
+impl Board<Black> {
+    /// Zobrist key of the position.
+    pub fn key(&self) -> u64 {
+        self.piece_key() ^ BLACK_TO_MOVE
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::engine::board::Square;
+
+    #[test]
+    fn test_key() {
+        let start = Board::default();
+        let moved = Board::<White>::default()
+            .set_piece(PieceType::NoPiece, Square::G1)
+            .set_piece(PieceType::WhiteKnight, Square::F3);
+        assert_ne!(start.key(), moved.key());
+
+        let back = moved
+            .set_piece(PieceType::NoPiece, Square::F3)
+            .set_piece(PieceType::WhiteKnight, Square::G1);
+        assert_eq!(start.key(), back.key());
+
+        let black = Board::<Black>::empty().set_piece(PieceType::WhiteKing, Square::E1);
+        let white = Board::<White>::empty().set_piece(PieceType::WhiteKing, Square::E1);
+        assert_eq!(black.key() ^ white.key(), BLACK_TO_MOVE);
+
+        let keys: Vec<u64> = PIECE_KEYS.iter().flatten().copied().collect();
+        assert!(keys.iter().all(|&key| key != 0 && key != BLACK_TO_MOVE));
+        let mut unique = keys.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(unique.len(), keys.len());
+    }
+}
@@ -0,0 +1,512 @@
+use crate::{
+    engine::{
+        board::{Black, Board, SQUARES, Square, White},
+        castling::CastlingRights,
+        history::History,
+        moves::Move,
+        pieces::PieceType,
+    },
+    pgn::GameResult,
+};
+use ::std::{error::Error, fmt::Display, mem, time::Duration};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Side {
+    White,
+    Black,
+}
+
+impl Side {
+    pub fn opponent(self) -> Self {
+        match self {
+            Self::White => Self::Black,
+            Self::Black => Self::White,
+        }
+    }
+}
+
+/// The board with whichever side is to move.
+pub enum Position {
+    White(Board<White>),
+    Black(Board<Black>),
+}
+
+impl Position {
+    pub fn side_to_move(&self) -> Side {
+        match self {
+            Self::White(_) => Side::White,
+            Self::Black(_) => Side::Black,
+        }
+    }
+
+    pub fn key(&self) -> u64 {
+        match self {
+            Self::White(board) => board.key(),
+            Self::Black(board) => board.key(),
+        }
+    }
+
+    pub fn get_piece(&self, square: Square) -> PieceType {
+        match self {
+            Self::White(board) => board.get_piece(square),
+            Self::Black(board) => board.get_piece(square),
+        }
+    }
+
+    pub fn is_insufficient_material(&self) -> bool {
+        match self {
+            Self::White(board) => board.is_insufficient_material(),
+            Self::Black(board) => board.is_insufficient_material(),
+        }
+    }
+
+    pub fn has_mating_material(&self, side: Side) -> bool {
+        let white = side == Side::White;
+        match self {
+            Self::White(board) => board.has_mating_material(white),
+            Self::Black(board) => board.has_mating_material(white),
+        }
+    }
+
+    fn play(self, mv: Move) -> Self {
+        match self {
+            Self::White(board) => Self::Black(board.play(mv)),
+            Self::Black(board) => Self::White(board.play(mv)),
+        }
+    }
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::White(board) => write!(f, "{board}"),
+            Self::Black(board) => write!(f, "{board}"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Status {
+    Ongoing,
+    ThreefoldRepetition,
+    FiftyMoves,
+    InsufficientMaterial,
+    /// The given side resigned.
+    Resignation(Side),
+    /// The given side ran out of time.
+    Timeout(Side),
+    /// The given side ran out of time, but its opponent could not mate it by
+    /// any series of legal moves.
+    TimeoutDraw(Side),
+    DrawAgreed,
+}
+
+impl Status {
+    pub fn is_over(&self) -> bool {
+        *self != Self::Ongoing
+    }
+
+    pub fn result(&self) -> GameResult {
+        match self {
+            Self::Ongoing => GameResult::Unknown,
+            Self::ThreefoldRepetition
+            | Self::FiftyMoves
+            | Self::InsufficientMaterial
+            | Self::TimeoutDraw(_)
+            | Self::DrawAgreed => GameResult::Draw,
+            Self::Resignation(Side::White) | Self::Timeout(Side::White) => GameResult::BlackWins,
+            Self::Resignation(Side::Black) | Self::Timeout(Side::Black) => GameResult::WhiteWins,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum GameError {
+    GameOver(Status),
+    /// The origin square does not hold a piece of the side to move.
+    NotYourPiece(Square),
+}
+
+impl Display for GameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::GameOver(status) => write!(f, "the game is over: {status:?}"),
+            Self::NotYourPiece(square) => write!(f, "no piece of the side to move on {square}"),
+        }
+    }
+}
+
+impl Error for GameError {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Clock {
+    pub remaining: Duration,
+    pub increment: Duration,
+}
+
+/// A game in progress: the board, the moves that led to it, the clocks, and
+/// how it ended.
+pub struct Game {
+    position: Position,
+    history: History,
+    moves: Vec<Move>,
+    clocks: Option<[Clock; 2]>,
+    /// Endings decided by the players rather than the board.
+    ending: Option<Status>,
+    castling: CastlingRights,
+    /// Whether the last move was a double pawn push next to an enemy pawn,
+    /// leaving an en passant capture the next position will not have.
+    en_passant: bool,
+}
+
+/// Castling rights with every king and rook on their first rank, as `KQkq`
+/// would give in X-FEN.
+fn initial_castling(board: &Board<White>) -> CastlingRights {
+    let mut rights = CastlingRights::default();
+    for letter in ["K", "Q", "k", "q"] {
+        if let Ok(right) = CastlingRights::parse(letter, board) {
+            for side in 0..2 {
+                rights.white[side] = rights.white[side].or(right.white[side]);
+                rights.black[side] = rights.black[side].or(right.black[side]);
+            }
+        }
+    }
+    rights
+}
+
+impl Game {
+    /// Start a game from `board`, assuming every king and rook on their
+    /// first rank may still castle.
+    pub fn new(board: Board<White>) -> Self {
+        Game {
+            history: History::new(board.key(), 0),
+            castling: initial_castling(&board),
+            position: Position::White(board),
+            moves: Vec::new(),
+            clocks: None,
+            ending: None,
+            en_passant: false,
+        }
+    }
+
+    /// Start with the given castling rights instead of the assumed ones.
+    pub fn with_castling(mut self, castling: CastlingRights) -> Self {
+        self.castling = castling;
+        self
+    }
+
+    /// Give both sides `time` on the clock, plus `increment` after every move.
+    pub fn with_clock(mut self, time: Duration, increment: Duration) -> Self {
+        let clock = Clock {
+            remaining: time,
+            increment,
+        };
+        self.clocks = Some([clock, clock]);
+        self
+    }
+
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    pub fn side_to_move(&self) -> Side {
+        self.position.side_to_move()
+    }
+
+    pub fn clock(&self, side: Side) -> Option<Clock> {
+        self.clocks.map(|clocks| clocks[side as usize])
+    }
+
+    /// Play `mv` for the side to move, which spent `elapsed` thinking on it.
+    /// The move itself is trusted to be legal.
+    ///
+    /// If `elapsed` uses up the mover's time, the move is not played and the
+    /// game ends, returning `GameError::GameOver` with the timeout.
+    pub fn play(&mut self, mv: Move, elapsed: Duration) -> Result<(), GameError> {
+        let status = self.status();
+        if status.is_over() {
+            return Err(GameError::GameOver(status));
+        }
+
+        let side = self.side_to_move();
+        let piece = self.position.get_piece(mv.from);
+        let own = match side {
+            Side::White => piece.is_white(),
+            Side::Black => piece.is_black(),
+        };
+        if !own {
+            return Err(GameError::NotYourPiece(mv.from));
+        }
+
+        if let Some(clock) = self
+            .clocks
+            .as_mut()
+            .map(|clocks| &mut clocks[side as usize])
+        {
+            match clock.remaining.checked_sub(elapsed) {
+                Some(remaining) if !remaining.is_zero() => {
+                    clock.remaining = remaining + clock.increment;
+                }
+                _ => {
+                    clock.remaining = Duration::ZERO;
+                    let status = if self.position.has_mating_material(side.opponent()) {
+                        Status::Timeout(side)
+                    } else {
+                        Status::TimeoutDraw(side)
+                    };
+                    self.ending = Some(status);
+                    return Err(GameError::GameOver(status));
+                }
+            }
+        }
+
+        // A king taking its own rook is Chess960 castling, not a capture.
+        let target = self.position.get_piece(mv.to);
+        let capture = match side {
+            Side::White => target.is_black(),
+            Side::Black => target.is_white(),
+        };
+        let pawn = matches!(piece, PieceType::WhitePawn | PieceType::BlackPawn);
+        let irreversible = pawn || capture;
+
+        // The key leaves out castling and en passant rights, so a change in
+        // them keeps earlier positions from counting as repetitions.
+        let castling = self.castling;
+        self.lose_castling(piece, mv);
+        let boundary = self.castling != castling || self.en_passant;
+        let enemy_pawn = match side {
+            Side::White => PieceType::BlackPawn,
+            Side::Black => PieceType::WhitePawn,
+        };
+        self.en_passant = pawn
+            && mv.from.rank().abs_diff(mv.to.rank()) == 2
+            && [mv.to.file().checked_sub(1), Some(mv.to.file() + 1)]
+                .into_iter()
+                .flatten()
+                .filter(|&file| file < 8)
+                .any(|file| {
+                    let square = SQUARES[mv.to.rank() as usize * 8 + file as usize];
+                    self.position.get_piece(square) == enemy_pawn
+                });
+
+        let position = mem::replace(&mut self.position, Position::White(Board::empty()));
+        self.position = position.play(mv);
+        if boundary && !irreversible {
+            self.history.push_boundary(self.position.key());
+        } else {
+            self.history.push(self.position.key(), irreversible);
+        }
+        self.moves.push(mv);
+        Ok(())
+    }
+
+    /// Drop the castling rights lost by moving `piece` along `mv`: all of a
+    /// king's, and those of a rook leaving or captured on its square.
+    fn lose_castling(&mut self, piece: PieceType, mv: Move) {
+        match piece {
+            PieceType::WhiteKing => self.castling.white = [None; 2],
+            PieceType::BlackKing => self.castling.black = [None; 2],
+            _ => {}
+        }
+        for (rights, rank) in [(&mut self.castling.white, 0), (&mut self.castling.black, 7)] {
+            for right in rights.iter_mut() {
+                let on_rook =
+                    |square: Square| square.rank() == rank && Some(square.file()) == *right;
+                if on_rook(mv.from) || on_rook(mv.to) {
+                    *right = None;
+                }
+            }
+        }
+    }
+
+    pub fn resign(&mut self, side: Side) {
+        self.ending.get_or_insert(Status::Resignation(side));
+    }
+
+    pub fn agree_draw(&mut self) {
+        self.ending.get_or_insert(Status::DrawAgreed);
+    }
+
+    /// Whether the game is over, and why.
+    pub fn status(&self) -> Status {
+        if let Some(ending) = self.ending {
+            ending
+        } else if self.history.is_threefold() {
+            Status::ThreefoldRepetition
+        } else if self.history.is_fifty_moves() {
+            Status::FiftyMoves
+        } else if self.position.is_insufficient_material() {
+            Status::InsufficientMaterial
+        } else {
+            Status::Ongoing
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn play(game: &mut Game, moves: &str) {
+        for mv in moves.split_whitespace() {
+            game.play(mv.parse().unwrap(), Duration::ZERO).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_repetition() {
+        let mut game = Game::new(Board::default());
+        play(&mut game, "g1f3 g8f6 f3g1 f6g8 g1f3 g8f6 f3g1");
+        assert_eq!(game.status(), Status::Ongoing);
+        assert_eq!(game.side_to_move(), Side::Black);
+
+        play(&mut game, "f6g8");
+        assert_eq!(game.status(), Status::ThreefoldRepetition);
+        assert_eq!(game.status().result(), GameResult::Draw);
+        assert_eq!(
+            game.play("e2e4".parse().unwrap(), Duration::ZERO),
+            Err(GameError::GameOver(Status::ThreefoldRepetition))
+        );
+    }
+
+    #[test]
+    fn test_players() {
+        let mut game = Game::new(Board::default());
+        assert_eq!(
+            game.play("e7e5".parse().unwrap(), Duration::ZERO),
+            Err(GameError::NotYourPiece(Square::E7))
+        );
+
+        play(&mut game, "e2e4");
+        game.resign(Side::Black);
+        assert_eq!(game.status(), Status::Resignation(Side::Black));
+        assert_eq!(game.status().result(), GameResult::WhiteWins);
+
+        // The first ending sticks.
+        game.agree_draw();
+        assert_eq!(game.status(), Status::Resignation(Side::Black));
+    }
+
+    #[test]
+    fn test_clock() {
+        let mut game =
+            Game::new(Board::default()).with_clock(Duration::from_secs(10), Duration::from_secs(1));
+        game.play("e2e4".parse().unwrap(), Duration::from_secs(4))
+            .unwrap();
+        assert_eq!(
+            game.clock(Side::White).unwrap().remaining,
+            Duration::from_secs(7)
+        );
+
+        assert_eq!(
+            game.play("e7e5".parse().unwrap(), Duration::from_secs(10)),
+            Err(GameError::GameOver(Status::Timeout(Side::Black)))
+        );
+        assert_eq!(game.status(), Status::Timeout(Side::Black));
+        assert_eq!(game.status().result(), GameResult::WhiteWins);
+        assert_eq!(game.moves().len(), 1);
+    }
+
+    #[test]
+    fn test_timeout_draw() {
+        // White flags, but Black has only a knight, and a queen cannot hem
+        // White's king in for it.
+        let board = Board::<White>::empty()
+            .set_piece(PieceType::WhiteKing, Square::E1)
+            .set_piece(PieceType::WhiteQueen, Square::D1)
+            .set_piece(PieceType::BlackKing, Square::E8)
+            .set_piece(PieceType::BlackKnight, Square::B8);
+        let mut game = Game::new(board).with_clock(Duration::from_secs(1), Duration::ZERO);
+        assert_eq!(
+            game.play("d1d2".parse().unwrap(), Duration::from_secs(2)),
+            Err(GameError::GameOver(Status::TimeoutDraw(Side::White)))
+        );
+        assert_eq!(game.status().result(), GameResult::Draw);
+        assert!(game.moves().is_empty());
+
+        // With a pawn, White's king can be hemmed in and mated: a loss.
+        let board = Board::<White>::empty()
+            .set_piece(PieceType::WhiteKing, Square::E1)
+            .set_piece(PieceType::WhitePawn, Square::A2)
+            .set_piece(PieceType::BlackKing, Square::E8)
+            .set_piece(PieceType::BlackKnight, Square::B8);
+        let mut game = Game::new(board).with_clock(Duration::from_secs(1), Duration::ZERO);
+        assert_eq!(
+            game.play("a2a3".parse().unwrap(), Duration::from_secs(2)),
+            Err(GameError::GameOver(Status::Timeout(Side::White)))
+        );
+    }
+
+    #[test]
+    fn test_castling_rights_repetition() {
+        let board = Board::<White>::empty()
+            .set_piece(PieceType::WhiteKing, Square::E1)
+            .set_piece(PieceType::WhiteRook, Square::H1)
+            .set_piece(PieceType::BlackKing, Square::E8)
+            .set_piece(PieceType::BlackRook, Square::H8);
+        let mut game = Game::new(board);
+        // The starting position could still castle, so it is not repeated.
+        play(&mut game, "e1f1 e8f8 f1e1 f8e8 e1f1 e8f8 f1e1 f8e8");
+        assert_eq!(game.status(), Status::Ongoing);
+        assert_eq!(game.history.halfmove_clock(), 8);
+
+        // Kings on f1 and f8 without rights, for the third time.
+        play(&mut game, "e1f1 e8f8");
+        assert_eq!(game.status(), Status::ThreefoldRepetition);
+    }
+
+    #[test]
+    fn test_en_passant_repetition() {
+        let board = Board::<White>::empty()
+            .set_piece(PieceType::WhiteKing, Square::A1)
+            .set_piece(PieceType::WhiteKnight, Square::G1)
+            .set_piece(PieceType::WhitePawn, Square::E2)
+            .set_piece(PieceType::BlackKing, Square::A8)
+            .set_piece(PieceType::BlackKnight, Square::G8)
+            .set_piece(PieceType::BlackPawn, Square::D4);
+        let mut game = Game::new(board);
+        // After e2e4, d4xe3 is possible; the same placement later is not.
+        play(&mut game, "e2e4 g8f6 g1f3 f6g8 f3g1 g8f6 g1f3 f6g8 f3g1");
+        assert_eq!(game.status(), Status::Ongoing);
+
+        play(&mut game, "g8f6");
+        assert_eq!(game.status(), Status::ThreefoldRepetition);
+    }
+
+    #[test]
+    fn test_castling_keeps_clock() {
+        let board = Board::<White>::empty()
+            .set_piece(PieceType::WhiteRook, Square::A1)
+            .set_piece(PieceType::WhiteKing, Square::E1)
+            .set_piece(PieceType::WhiteRook, Square::H1)
+            .set_piece(PieceType::BlackKing, Square::E8)
+            .set_piece(PieceType::BlackRook, Square::F8);
+        let mut game = Game::new(board);
+        play(&mut game, "a1a2 e8d8 a2a1 d8e8");
+        assert_eq!(game.history.halfmove_clock(), 4);
+
+        play(&mut game, "e1h1");
+        assert_eq!(game.position().get_piece(Square::G1), PieceType::WhiteKing);
+        assert_eq!(game.history.halfmove_clock(), 5);
+
+        // The rook now on f1 can be captured.
+        play(&mut game, "f8f1");
+        assert_eq!(game.history.halfmove_clock(), 0);
+    }
+
+    #[test]
+    fn test_material() {
+        let board = Board::<White>::empty()
+            .set_piece(PieceType::WhiteKing, Square::E1)
+            .set_piece(PieceType::WhiteBishop, Square::D4)
+            .set_piece(PieceType::BlackKing, Square::E8)
+            .set_piece(PieceType::BlackRook, Square::H8);
+        let mut game = Game::new(board);
+        play(&mut game, "d4h8");
+        assert_eq!(game.status(), Status::InsufficientMaterial);
+    }
+}
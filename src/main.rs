@@ -1,10 +1,15 @@
 pub mod engine;
+pub mod game;
+pub mod pgn;
+pub mod stats;
 
 use engine::board;
 
-use crate::engine::bitboard;
+use crate::engine::{bitboard, kpk};
 
 fn main() {
+    kpk::init();
+
     let board = board::Board::default();
     println!("{board}");
 
@@ -0,0 +1,596 @@
+use ::std::{
+    error::Error,
+    fmt::{Display, Write},
+};
+
+/// Maximum length of a movetext line when writing games back out.
+const LINE_WIDTH: usize = 80;
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum GameResult {
+    WhiteWins,
+    BlackWins,
+    Draw,
+    #[default]
+    Unknown,
+}
+
+impl GameResult {
+    fn parse(token: &str) -> Option<Self> {
+        match token {
+            "1-0" => Some(Self::WhiteWins),
+            "0-1" => Some(Self::BlackWins),
+            "1/2-1/2" => Some(Self::Draw),
+            "*" => Some(Self::Unknown),
+            _ => None,
+        }
+    }
+}
+
+impl Display for GameResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::WhiteWins => "1-0",
+                Self::BlackWins => "0-1",
+                Self::Draw => "1/2-1/2",
+                Self::Unknown => "*",
+            }
+        )
+    }
+}
+
+/// One move of the game tree, with its annotations and the alternatives to it.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Node {
+    pub san: String,
+    pub nags: Vec<u8>,
+    pub comments: Vec<String>,
+    /// Lines played instead of this move.
+    pub variations: Vec<Vec<Node>>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Game {
+    pub tags: Vec<(String, String)>,
+    /// Comments before the first move.
+    pub comments: Vec<String>,
+    pub moves: Vec<Node>,
+    pub result: GameResult,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum PgnError {
+    UnterminatedComment,
+    UnterminatedTag,
+    UnbalancedVariation,
+    UnexpectedToken(String),
+}
+
+impl Display for PgnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnterminatedComment => write!(f, "unterminated comment"),
+            Self::UnterminatedTag => write!(f, "unterminated tag pair"),
+            Self::UnbalancedVariation => write!(f, "unbalanced variation parentheses"),
+            Self::UnexpectedToken(token) => write!(f, "unexpected token `{token}`"),
+        }
+    }
+}
+
+impl Error for PgnError {}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token {
+    Tag(String, String),
+    Comment(String),
+    Nag(u8),
+    Open,
+    Close,
+    Result(GameResult),
+    San(String),
+}
+
+fn nag_of_suffix(suffix: &str) -> Option<u8> {
+    match suffix {
+        "!" => Some(1),
+        "?" => Some(2),
+        "!!" => Some(3),
+        "??" => Some(4),
+        "!?" => Some(5),
+        "?!" => Some(6),
+        _ => None,
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, PgnError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    let mut line_start = true;
+
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '\n' => {
+                line_start = true;
+                continue;
+            }
+            // Escape mechanism: the whole line is ignored.
+            '%' if line_start => {
+                for (_, c) in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+                line_start = true;
+                continue;
+            }
+            ';' => {
+                let comment: String = chars
+                    .by_ref()
+                    .map(|(_, c)| c)
+                    .take_while(|&c| c != '\n')
+                    .collect();
+                tokens.push(Token::Comment(comment.trim().to_string()));
+                line_start = true;
+                continue;
+            }
+            '{' => {
+                let mut comment = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '}')) => break,
+                        Some((_, c)) => comment.push(c),
+                        None => return Err(PgnError::UnterminatedComment),
+                    }
+                }
+                tokens.push(Token::Comment(
+                    comment.split_whitespace().collect::<Vec<_>>().join(" "),
+                ));
+            }
+            '[' => {
+                let mut name = String::new();
+                while let Some(&(_, c)) = chars.peek() {
+                    if c == '"' || c == ']' {
+                        break;
+                    }
+                    name.push(c);
+                    chars.next();
+                }
+                if chars.next().map(|(_, c)| c) != Some('"') {
+                    return Err(PgnError::UnterminatedTag);
+                }
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, c)) => value.push(c),
+                            None => return Err(PgnError::UnterminatedTag),
+                        },
+                        Some((_, '"')) => break,
+                        Some((_, c)) => value.push(c),
+                        None => return Err(PgnError::UnterminatedTag),
+                    }
+                }
+                loop {
+                    match chars.next() {
+                        Some((_, ']')) => break,
+                        Some((_, c)) if c.is_whitespace() => {}
+                        _ => return Err(PgnError::UnterminatedTag),
+                    }
+                }
+                tokens.push(Token::Tag(name.trim().to_string(), value));
+            }
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            c if c.is_whitespace() => {}
+            _ => {
+                let mut end = start + c.len_utf8();
+                while let Some(&(i, c)) = chars.peek() {
+                    if c.is_whitespace() || "{}()[];".contains(c) {
+                        break;
+                    }
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+                tokens.extend(symbol(&input[start..end])?);
+            }
+        }
+        line_start = false;
+    }
+
+    Ok(tokens)
+}
+
+/// Split a whitespace-delimited symbol into move number, move, and annotation tokens.
+fn symbol(word: &str) -> Result<Vec<Token>, PgnError> {
+    if let Some(result) = GameResult::parse(word) {
+        return Ok(vec![Token::Result(result)]);
+    }
+    if let Some(nag) = word.strip_prefix('$') {
+        return nag
+            .parse()
+            .map(|nag| vec![Token::Nag(nag)])
+            .map_err(|_| PgnError::UnexpectedToken(word.to_string()));
+    }
+    if let Some(nag) = nag_of_suffix(word) {
+        return Ok(vec![Token::Nag(nag)]);
+    }
+
+    // Move number indications: `12.`, `12...`, or glued to the move as in
+    // `12.e4`. Digits not followed by a dot are a move, such as `0-0`.
+    let unnumbered = word.trim_start_matches(|c: char| c.is_ascii_digit());
+    let word = if unnumbered.is_empty() || unnumbered.starts_with('.') {
+        unnumbered.trim_start_matches('.')
+    } else {
+        word
+    };
+    if word.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let san_end = word.trim_end_matches(['!', '?']).len();
+    let (san, suffix) = word.split_at(san_end);
+    if san.is_empty()
+        || !san
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "=+#-".contains(c))
+    {
+        return Err(PgnError::UnexpectedToken(word.to_string()));
+    }
+
+    // Castling written with zeros, in its standard letter form.
+    let san = match san.trim_end_matches(['+', '#']) {
+        "0-0" | "0-0-0" => san.replace('0', "O"),
+        _ => san.to_string(),
+    };
+    let mut tokens = vec![Token::San(san)];
+    if !suffix.is_empty() {
+        tokens.push(Token::Nag(
+            nag_of_suffix(suffix).ok_or_else(|| PgnError::UnexpectedToken(word.to_string()))?,
+        ));
+    }
+    Ok(tokens)
+}
+
+/// Parse every game in a PGN database.
+pub fn parse(input: &str) -> Result<Vec<Game>, PgnError> {
+    let mut games: Vec<Game> = Vec::new();
+    let mut game = Game::default();
+    // Stack of lines being filled: the mainline, then any open variations.
+    let mut lines: Vec<Vec<Node>> = vec![Vec::new()];
+    let mut started = false;
+    // Comments opening a variation, kept with its first move.
+    let mut pending = Vec::new();
+
+    for token in tokenize(input)? {
+        match token {
+            Token::Tag(name, value) => {
+                if !game.moves.is_empty() || !lines[0].is_empty() {
+                    return Err(PgnError::UnexpectedToken(format!("[{name}")));
+                }
+                game.tags.push((name, value));
+                started = true;
+            }
+            Token::Comment(comment) => {
+                // A comment after a result, before anything else of the next
+                // game, still belongs to the finished game.
+                if let (false, Some(finished)) = (started, games.last_mut()) {
+                    match finished.moves.last_mut() {
+                        Some(node) => node.comments.push(comment),
+                        None => finished.comments.push(comment),
+                    }
+                    continue;
+                }
+                let depth = lines.len();
+                match lines.last_mut().and_then(|line| line.last_mut()) {
+                    Some(node) => node.comments.push(comment),
+                    None if depth == 1 => game.comments.push(comment),
+                    None => pending.push(comment),
+                }
+                started = true;
+            }
+            Token::Nag(nag) => match lines.last_mut().and_then(|line| line.last_mut()) {
+                Some(node) => node.nags.push(nag),
+                None => return Err(PgnError::UnexpectedToken(format!("${nag}"))),
+            },
+            Token::San(san) => {
+                lines
+                    .last_mut()
+                    .expect("the mainline is never popped")
+                    .push(Node {
+                        san,
+                        comments: std::mem::take(&mut pending),
+                        ..Node::default()
+                    });
+                started = true;
+            }
+            Token::Open => {
+                if lines.last().is_none_or(|line| line.is_empty()) {
+                    return Err(PgnError::UnbalancedVariation);
+                }
+                lines.push(Vec::new());
+            }
+            Token::Close => {
+                if lines.len() < 2 {
+                    return Err(PgnError::UnbalancedVariation);
+                }
+                let variation = lines.pop().expect("checked above");
+                lines
+                    .last_mut()
+                    .and_then(|line| line.last_mut())
+                    .expect("variations open after a move")
+                    .variations
+                    .push(variation);
+            }
+            Token::Result(result) => {
+                if lines.len() != 1 {
+                    return Err(PgnError::UnbalancedVariation);
+                }
+                game.result = result;
+                game.moves = lines.pop().expect("the mainline is never popped");
+                games.push(game);
+                game = Game::default();
+                lines.push(Vec::new());
+                started = false;
+            }
+        }
+    }
+
+    if lines.len() != 1 {
+        return Err(PgnError::UnbalancedVariation);
+    }
+    if started {
+        // Tolerate a missing game termination marker on the last game.
+        game.moves = lines.pop().expect("the mainline is never popped");
+        games.push(game);
+    }
+    Ok(games)
+}
+
+/// Accumulates movetext words, wrapping lines at `LINE_WIDTH`.
+struct Wrapper {
+    text: String,
+    line: usize,
+}
+
+impl Wrapper {
+    fn word(&mut self, word: &str) {
+        if self.line > 0 && self.line + 1 + word.len() > LINE_WIDTH {
+            self.text.push('\n');
+            self.line = 0;
+        } else if self.line > 0 {
+            self.text.push(' ');
+            self.line += 1;
+        }
+        self.text.push_str(word);
+        self.line += word.len();
+    }
+
+    /// Comments may wrap between their own words.
+    fn comment(&mut self, comment: &str) {
+        if comment.contains('}') {
+            // A brace comment cannot hold `}`, so end the line with a
+            // rest-of-line comment instead.
+            let comment = comment.split_whitespace().collect::<Vec<_>>().join(" ");
+            if self.line > 0 {
+                self.text.push(' ');
+            }
+            self.text.push_str(&format!("; {comment}\n"));
+            self.line = 0;
+            return;
+        }
+        let mut words = comment.split_whitespace().peekable();
+        let mut first = true;
+        while let Some(word) = words.next() {
+            let open = if first { "{" } else { "" };
+            let close = if words.peek().is_none() { "}" } else { "" };
+            self.word(&format!("{open}{word}{close}"));
+            first = false;
+        }
+        if first {
+            self.word("{}");
+        }
+    }
+}
+
+impl Game {
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Ply of the first move, taken from the `FEN` tag when there is one.
+    fn first_ply(&self) -> usize {
+        let Some(fen) = self.tag("FEN") else {
+            return 0;
+        };
+        let mut fields = fen.split_whitespace().skip(1);
+        let black = fields.next() == Some("b");
+        let fullmove: usize = fields.nth(3).and_then(|n| n.parse().ok()).unwrap_or(1);
+        2 * fullmove.saturating_sub(1) + black as usize
+    }
+
+    fn write_line(wrapper: &mut Wrapper, line: &[Node], mut ply: usize) {
+        let mut number = true;
+        for node in line {
+            if ply.is_multiple_of(2) {
+                wrapper.word(&format!("{}. {}", ply / 2 + 1, node.san));
+            } else if number {
+                wrapper.word(&format!("{}... {}", ply / 2 + 1, node.san));
+            } else {
+                wrapper.word(&node.san);
+            }
+            for nag in &node.nags {
+                wrapper.word(&format!("${nag}"));
+            }
+            for comment in &node.comments {
+                wrapper.comment(comment);
+            }
+            for variation in &node.variations {
+                let mut sub = Wrapper {
+                    text: String::new(),
+                    line: 0,
+                };
+                Self::write_line(&mut sub, variation, ply);
+                // Re-flow the variation's words so its parentheses stay attached.
+                let mut words = sub.text.split_whitespace().peekable();
+                let mut first = true;
+                while let Some(word) = words.next() {
+                    let open = if first { "(" } else { "" };
+                    let close = if words.peek().is_none() { ")" } else { "" };
+                    wrapper.word(&format!("{open}{word}{close}"));
+                    first = false;
+                }
+            }
+            number = !node.comments.is_empty() || !node.variations.is_empty();
+            ply += 1;
+        }
+    }
+}
+
+impl Display for Game {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, value) in &self.tags {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            writeln!(f, "[{name} \"{value}\"]")?;
+        }
+        if !self.tags.is_empty() {
+            writeln!(f)?;
+        }
+
+        let mut wrapper = Wrapper {
+            text: String::new(),
+            line: 0,
+        };
+        for comment in &self.comments {
+            wrapper.comment(comment);
+        }
+        Self::write_line(&mut wrapper, &self.moves, self.first_ply());
+        wrapper.word(&self.result.to_string());
+        f.write_str(&wrapper.text)?;
+        f.write_char('\n')
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const GAME: &str = r#"[Event "Casual \"blitz\""]
+[White "Ambre"]
+[Black "Ambre"]
+[Result "1-0"]
+
+{Opening comment} 1. e4 e5 2. Nf3 Nc6 3. Bb5 a6 $1 (3... Nf6 4. O-O (4. d3) 4...
+Nxe4) 4. Ba4 Nf6 5. O-O Be7!? ; a rest-of-line comment
+% an escaped line
+6. Re1 b5 7. Bb3 d6 8. c3 O-O 9. h3 Nb8 10. d4 Nbd7 11. c4 c6 12. cxb5 axb5
+13. Nc3 Bb7 14. Bg5 b4 15. Nb1 h6 16. Bh4 c5 17. dxe5 Nxe4 18. Bxe7 Qxe7
+19. exd6 Qf6 20. Nbd2 Nxd6 21. Nc4 Nxc4 22. Bxc4 Nb6 23. Ne5 Rae8
+24. Bxf7+ Rxf7 25. Nxf7 Rxe1+ 26. Qxe1 Kxf7 27. Qe3 Qg5 28. Qxg5 hxg5
+29. b3 Ke6 30. a3 Kd6 31. axb4 cxb4 32. Ra5 Nd5 33. f3 Bc8 34. Kf2 Bf5
+35. Ra7 g6 36. Ra6+ Kc5 37. Ke1 Nf4 38. g3 Nxh3 39. Kd2 Kb5 40. Rd6 Kc5
+41. Ra6 Nf2 42. g4 Bd3 43. Re6 1-0
+
+[Event "Second"]
+
+1. d4 d5 *
+"#;
+
+    #[test]
+    fn test_parse() {
+        let games = parse(GAME).unwrap();
+        assert_eq!(games.len(), 2);
+
+        let game = &games[0];
+        assert_eq!(game.tag("Event"), Some("Casual \"blitz\""));
+        assert_eq!(game.comments, vec!["Opening comment".to_string()]);
+        assert_eq!(game.moves.len(), 85);
+        assert_eq!(game.result, GameResult::WhiteWins);
+
+        let a6 = &game.moves[5];
+        assert_eq!(a6.san, "a6");
+        assert_eq!(a6.nags, vec![1]);
+        assert_eq!(a6.variations.len(), 1);
+        let variation = &a6.variations[0];
+        assert_eq!(variation.len(), 3);
+        assert_eq!(variation[1].san, "O-O");
+        assert_eq!(variation[1].variations[0][0].san, "d3");
+
+        let be7 = &game.moves[9];
+        assert_eq!(be7.nags, vec![5]);
+        assert_eq!(be7.comments, vec!["a rest-of-line comment".to_string()]);
+        assert_eq!(game.moves[46].san, "Bxf7+");
+
+        assert_eq!(games[1].moves.len(), 2);
+        assert_eq!(games[1].result, GameResult::Unknown);
+    }
+
+    #[test]
+    fn test_trailing_comment() {
+        let games = parse("1. e4 e5 1-0 {done}").unwrap();
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].moves[1].comments, vec!["done".to_string()]);
+
+        let games = parse("1-0 {forfeit}").unwrap();
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].comments, vec!["forfeit".to_string()]);
+    }
+
+    #[test]
+    fn test_zero_castling() {
+        let games = parse("1. e4 e5 2. Nf3 Nc6 3. Bc4 Bc5 4. 0-0 d6 5.0-0-0+ *").unwrap();
+        let moves = &games[0].moves;
+        assert_eq!(moves.len(), 9);
+        assert_eq!(moves[6].san, "O-O");
+        assert_eq!(moves[7].san, "d6");
+        assert_eq!(moves[8].san, "O-O-O+");
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(parse("1. e4 {oops"), Err(PgnError::UnterminatedComment));
+        assert_eq!(parse("[Event \"x"), Err(PgnError::UnterminatedTag));
+        assert_eq!(parse("1. e4 (1. d4"), Err(PgnError::UnbalancedVariation));
+        assert_eq!(parse("1. e4 e5) 1-0"), Err(PgnError::UnbalancedVariation));
+        assert_eq!(
+            parse("1. e4 @@ 1-0"),
+            Err(PgnError::UnexpectedToken("@@".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_write() {
+        let games = parse(GAME).unwrap();
+        let text = games[0].to_string();
+
+        assert!(text.starts_with("[Event \"Casual \\\"blitz\\\"\"]\n"));
+        let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        assert!(flat.contains("3. Bb5 a6 $1 (3... Nf6 4. O-O (4. d3) 4... Nxe4) 4. Ba4"));
+        assert!(flat.contains("Be7 $5 {a rest-of-line comment} 6. Re1"));
+        assert!(text.lines().all(|line| line.len() <= LINE_WIDTH));
+        assert!(text.trim_end().ends_with("43. Re6 1-0"));
+
+        assert_eq!(parse(&text).unwrap()[0], games[0]);
+    }
+
+    #[test]
+    fn test_write_brace_comment() {
+        let games = parse("1. e4 ; see {x}\n1... e5 {fine} *").unwrap();
+        assert_eq!(games[0].moves[0].comments, vec!["see {x}".to_string()]);
+        let text = games[0].to_string();
+        assert!(text.contains("1. e4 ; see {x}\n1... e5 {fine} *"));
+        assert_eq!(parse(&text).unwrap()[0], games[0]);
+    }
+
+    #[test]
+    fn test_write_from_fen() {
+        let games =
+            parse("[FEN \"8/8/8/8/8/8/8/K1k5 b - - 0 30\"]\n\n30... Kd2 31. Kb2 *").unwrap();
+        assert!(games[0].to_string().ends_with("30... Kd2 31. Kb2 *\n"));
+    }
+}
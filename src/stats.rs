@@ -0,0 +1,241 @@
+use crate::pgn::GameResult;
+
+/// Quantile of the normal distribution for a two-sided 95% interval.
+const Z_95: f64 = 1.959_964;
+
+/// Elo difference giving an expected score `score` under the logistic model.
+fn elo(score: f64) -> f64 {
+    -400.0 * (1.0 / score - 1.0).log10()
+}
+
+/// Expected score of a player `elo` points stronger.
+fn expected_score(elo: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-elo / 400.0))
+}
+
+/// Win, draw and loss counts of one engine against another.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct Wdl {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+/// Results of game pairs played from the same opening with colours
+/// reversed, by points the pair scored: index `i` counts pairs worth `i / 2`
+/// points (loss-loss, loss-draw, draw-draw or win-loss, win-draw, win-win).
+///
+/// Pairing cancels out most of the opening's bias, so the variance is
+/// smaller than the same games counted as `Wdl`.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct Pentanomial(pub [u32; 5]);
+
+impl Pentanomial {
+    /// Record a pair in which the engine played White in the first game
+    /// and Black in the second. Returns `false`, recording nothing, if
+    /// either game has no result.
+    pub fn add_pair(&mut self, as_white: GameResult, as_black: GameResult) -> bool {
+        let half_points = |result, win| match result {
+            GameResult::Unknown => None,
+            GameResult::Draw => Some(1),
+            result if result == win => Some(2),
+            _ => Some(0),
+        };
+        match (
+            half_points(as_white, GameResult::WhiteWins),
+            half_points(as_black, GameResult::BlackWins),
+        ) {
+            (Some(first), Some(second)) => {
+                self.0[first + second] += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Mean and variance of the per-game score over a number of independent
+/// samples, which are games for `Wdl` and pairs for `Pentanomial`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Sample {
+    pub count: u32,
+    pub mean: f64,
+    /// Variance of a single sample.
+    pub variance: f64,
+}
+
+impl Sample {
+    /// Sample of outcomes valued `scores`, each seen `counts` times.
+    fn new(scores: &[f64], counts: &[u32]) -> Option<Self> {
+        let count: u32 = counts.iter().sum();
+        if count == 0 {
+            return None;
+        }
+        let n = count as f64;
+        let mean = scores
+            .iter()
+            .zip(counts)
+            .map(|(score, &c)| score * c as f64)
+            .sum::<f64>()
+            / n;
+        let variance = scores
+            .iter()
+            .zip(counts)
+            .map(|(score, &c)| (score - mean).powi(2) * c as f64)
+            .sum::<f64>()
+            / n;
+        Some(Sample {
+            count,
+            mean,
+            variance,
+        })
+    }
+
+    /// Elo difference with its 95% confidence interval. A perfect or zero
+    /// score gives infinite bounds.
+    pub fn elo(&self) -> Elo {
+        let margin = Z_95 * (self.variance / self.count as f64).sqrt();
+        Elo {
+            diff: elo(self.mean),
+            lower: elo((self.mean - margin).max(0.0)),
+            upper: elo((self.mean + margin).min(1.0)),
+        }
+    }
+}
+
+impl Wdl {
+    /// `None` before the first game.
+    pub fn sample(&self) -> Option<Sample> {
+        Sample::new(&[1.0, 0.5, 0.0], &[self.wins, self.draws, self.losses])
+    }
+}
+
+impl Pentanomial {
+    /// `None` before the first pair.
+    pub fn sample(&self) -> Option<Sample> {
+        Sample::new(&[0.0, 0.25, 0.5, 0.75, 1.0], &self.0)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Elo {
+    pub diff: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SprtResult {
+    Continue,
+    /// The patch is no better than `elo0`.
+    AcceptH0,
+    /// The patch is at least `elo1` better.
+    AcceptH1,
+}
+
+/// Sequential probability ratio test of H0: the Elo difference is `elo0`
+/// against H1: it is `elo1`, with false positive rate `alpha` and false
+/// negative rate `beta`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Sprt {
+    pub elo0: f64,
+    pub elo1: f64,
+    pub alpha: f64,
+    pub beta: f64,
+}
+
+impl Sprt {
+    /// Log-likelihood ratio below which H0 is accepted, and above which H1 is.
+    pub fn bounds(&self) -> (f64, f64) {
+        (
+            (self.beta / (1.0 - self.alpha)).ln(),
+            ((1.0 - self.beta) / self.alpha).ln(),
+        )
+    }
+
+    /// Log-likelihood ratio of the results, using the normal approximation
+    /// of the generalised SPRT: each hypothesis is the sample's own
+    /// distribution shifted to the hypothesis' mean.
+    pub fn llr(&self, sample: &Sample) -> f64 {
+        if sample.variance <= 0.0 {
+            return 0.0;
+        }
+        let (s0, s1) = (expected_score(self.elo0), expected_score(self.elo1));
+        sample.count as f64 * (s1 - s0) * (2.0 * sample.mean - s0 - s1) / (2.0 * sample.variance)
+    }
+
+    pub fn test(&self, sample: &Sample) -> SprtResult {
+        let (lower, upper) = self.bounds();
+        let llr = self.llr(sample);
+        if llr <= lower {
+            SprtResult::AcceptH0
+        } else if llr >= upper {
+            SprtResult::AcceptH1
+        } else {
+            SprtResult::Continue
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn wdl(wins: u32, draws: u32, losses: u32) -> Sample {
+        Wdl {
+            wins,
+            draws,
+            losses,
+        }
+        .sample()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_elo() {
+        assert_eq!(Wdl::default().sample(), None);
+
+        let elo = wdl(100, 50, 50).elo();
+        assert!((elo.diff - 88.74).abs() < 0.01);
+        assert!(elo.lower < elo.diff && elo.diff < elo.upper);
+        assert!(elo.lower > 0.0);
+
+        let even = wdl(10, 0, 10).elo();
+        assert_eq!(even.diff, 0.0);
+        assert!((even.upper + even.lower).abs() < 1e-9);
+
+        let perfect = wdl(5, 0, 0).elo();
+        assert_eq!(perfect.diff, f64::INFINITY);
+
+        // The same 200 games, paired so that each opening splits evenly
+        // except for the wins: pairing gives a narrower interval.
+        let mut pairs = Pentanomial::default();
+        for _ in 0..50 {
+            assert!(pairs.add_pair(GameResult::WhiteWins, GameResult::Draw));
+            assert!(pairs.add_pair(GameResult::BlackWins, GameResult::BlackWins));
+        }
+        assert!(!pairs.add_pair(GameResult::WhiteWins, GameResult::Unknown));
+        assert_eq!(pairs.0, [0, 0, 50, 50, 0]);
+        let paired = pairs.sample().unwrap().elo();
+        let unpaired = wdl(75, 100, 25).elo();
+        assert!((paired.diff - unpaired.diff).abs() < 1e-9);
+        assert!(paired.upper - paired.lower < unpaired.upper - unpaired.lower);
+    }
+
+    #[test]
+    fn test_sprt() {
+        let sprt = Sprt {
+            elo0: 0.0,
+            elo1: 5.0,
+            alpha: 0.05,
+            beta: 0.05,
+        };
+        let (lower, upper) = sprt.bounds();
+        assert!((lower + 2.944).abs() < 0.001 && (upper - 2.944).abs() < 0.001);
+
+        assert_eq!(sprt.test(&wdl(1000, 1000, 1000)), SprtResult::Continue);
+        assert_eq!(sprt.test(&wdl(30000, 30000, 30000)), SprtResult::AcceptH0);
+        assert_eq!(sprt.test(&wdl(1200, 1000, 1000)), SprtResult::AcceptH1);
+        assert_eq!(sprt.test(&wdl(0, 10, 0)), SprtResult::Continue);
+    }
+}